@@ -0,0 +1,46 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{env, fs, path::Path};
+
+#[path = "build/chain_monitor_api.rs"]
+mod chain_monitor_api;
+
+use chain_monitor_api::{ChainMonitorApi, CHAIN_MONITOR_DIR, README};
+
+fn main() {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let manifest_dir = Path::new(&manifest_dir);
+    let readme_path = manifest_dir.join(CHAIN_MONITOR_DIR).join(README);
+
+    println!("cargo::rerun-if-changed=build/chain_monitor_api.rs");
+    println!("cargo::rerun-if-changed={}", readme_path.display());
+    let api = ChainMonitorApi::parse(manifest_dir);
+    for path in &api.sources {
+        println!("cargo::rerun-if-changed={}", path.display());
+    }
+
+    for (file_name, name) in &api.undocumented {
+        println!(
+            "cargo::warning=chain_monitor/{file_name}: public function `{name}` has no doc-comment"
+        );
+    }
+    // The README is regenerated by a test, the build only reports when it is out of date.
+    if fs::read_to_string(&readme_path).ok().as_deref() != Some(api.render_readme().as_str()) {
+        println!(
+            "cargo::warning=chain_monitor/{README} is out of date, regenerate it with \
+             `UPDATE_README=1 cargo test -p broker chain_monitor_readme`"
+        );
+    }
+}
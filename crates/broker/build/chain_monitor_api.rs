@@ -0,0 +1,223 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Summary of the public chain monitor API, shared by the build script, which warns about
+// undocumented functions and a stale README, and by the test that regenerates the README.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use syn::{Attribute, ImplItem, Item, TraitItem, Type, Visibility};
+
+// Directory of the chain monitor module, relative to the crate root.
+pub const CHAIN_MONITOR_DIR: &str = "src/chain_monitor";
+
+// Summary of the chain monitor API, in the chain monitor directory.
+pub const README: &str = "README.md";
+
+// Types whose methods are listed in the summary.
+const SUMMARIZED_TYPES: [&str; 2] = ["ChainMonitorService", "ChainMonitorExt"];
+
+// Categories of the summary, in the order they are listed.
+const CATEGORIES: [&str; 4] = ["Fetching", "Subscription", "Estimation", "Proof-specific"];
+
+struct Method {
+    name: String,
+    summary: String,
+}
+
+pub struct ChainMonitorApi {
+    // Source files of the chain monitor module, sorted by path.
+    pub sources: Vec<PathBuf>,
+    // File name and name of each public function without a doc-comment.
+    pub undocumented: Vec<(String, String)>,
+    categories: BTreeMap<usize, Vec<Method>>,
+}
+
+impl ChainMonitorApi {
+    // Parses the sources of the chain monitor module of the crate at `manifest_dir`.
+    pub fn parse(manifest_dir: &Path) -> Self {
+        let mut sources: Vec<_> = fs::read_dir(manifest_dir.join(CHAIN_MONITOR_DIR))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+            .collect();
+        sources.sort();
+
+        let mut api =
+            Self { sources: Vec::new(), undocumented: Vec::new(), categories: BTreeMap::new() };
+        for path in &sources {
+            let file_name = path.file_name().unwrap().to_string_lossy();
+            let source = fs::read_to_string(path).unwrap();
+            let file = syn::parse_file(&source)
+                .unwrap_or_else(|err| panic!("failed to parse {}: {err}", path.display()));
+            for item in &file.items {
+                api.check_item(&file_name, item);
+            }
+        }
+        api.sources = sources;
+        api
+    }
+
+    // Renders the README summarizing the API.
+    pub fn render_readme(&self) -> String {
+        let mut readme = String::from(
+            "<!-- Generated from the chain monitor doc-comments by \
+             `UPDATE_README=1 cargo test -p broker chain_monitor_readme`. Do not edit. -->\n\n\
+             # Chain monitor API\n\n\
+             Public methods of `ChainMonitorService` and `ChainMonitorExt`, grouped by category.\n",
+        );
+        for (index, methods) in &self.categories {
+            readme.push_str(&format!("\n## {}\n\n", CATEGORIES[*index]));
+            let mut methods: Vec<_> = methods.iter().collect();
+            methods.sort_by(|a, b| a.name.cmp(&b.name));
+            for method in methods {
+                if method.summary.is_empty() {
+                    readme.push_str(&format!("- `{}`\n", method.name));
+                } else {
+                    readme.push_str(&format!("- `{}`: {}\n", method.name, method.summary));
+                }
+            }
+        }
+        readme
+    }
+
+    // Records undocumented `pub fn` items and collects the methods of the summarized types.
+    fn check_item(&mut self, file_name: &str, item: &Item) {
+        match item {
+            Item::Fn(item) if is_pub(&item.vis) => {
+                self.check_documented(file_name, &item.sig.ident.to_string(), &item.attrs);
+            }
+            Item::Impl(item) if item.trait_.is_none() => {
+                let summarized = type_name(&item.self_ty)
+                    .is_some_and(|name| SUMMARIZED_TYPES.contains(&name.as_str()));
+                for impl_item in &item.items {
+                    let ImplItem::Fn(method) = impl_item else { continue };
+                    if !is_pub(&method.vis) {
+                        continue;
+                    }
+                    let name = method.sig.ident.to_string();
+                    self.check_documented(file_name, &name, &method.attrs);
+                    if summarized {
+                        self.add_method(name, &method.attrs, is_stream(&method.sig.output));
+                    }
+                }
+            }
+            Item::Trait(item) if is_pub(&item.vis) => {
+                let summarized = SUMMARIZED_TYPES.contains(&item.ident.to_string().as_str());
+                for trait_item in &item.items {
+                    let TraitItem::Fn(method) = trait_item else { continue };
+                    let name = method.sig.ident.to_string();
+                    self.check_documented(file_name, &name, &method.attrs);
+                    if summarized {
+                        self.add_method(name, &method.attrs, is_stream(&method.sig.output));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn check_documented(&mut self, file_name: &str, name: &str, attrs: &[Attribute]) {
+        if doc_lines(attrs).iter().all(|line| line.trim().is_empty()) {
+            self.undocumented.push((file_name.to_string(), name.to_string()));
+        }
+    }
+
+    fn add_method(&mut self, name: String, attrs: &[Attribute], returns_stream: bool) {
+        // The summary is the first paragraph of the doc-comment, joined into a single line.
+        let summary = doc_lines(attrs)
+            .into_iter()
+            .map(|line| line.trim().to_string())
+            .take_while(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let category = categorize(&name, returns_stream);
+        self.categories.entry(category).or_default().push(Method { name, summary });
+    }
+}
+
+fn is_pub(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+fn type_name(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(path) => path.path.segments.last().map(|segment| segment.ident.to_string()),
+        _ => None,
+    }
+}
+
+fn is_stream(output: &syn::ReturnType) -> bool {
+    match output {
+        syn::ReturnType::Type(_, ty) => type_idents(ty).contains("Stream"),
+        syn::ReturnType::Default => false,
+    }
+}
+
+// Returns the identifiers of a path type or of the bounds of an `impl Trait` type.
+fn type_idents(ty: &Type) -> String {
+    match ty {
+        Type::ImplTrait(ty) => ty
+            .bounds
+            .iter()
+            .filter_map(|bound| match bound {
+                syn::TypeParamBound::Trait(bound) => {
+                    bound.path.segments.last().map(|segment| segment.ident.to_string())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(" + "),
+        Type::Path(path) => {
+            path.path.segments.iter().map(|s| s.ident.to_string()).collect::<Vec<_>>().join("::")
+        }
+        _ => String::new(),
+    }
+}
+
+// Returns the lines of the `///` doc-comment in `attrs`.
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value: syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(doc), .. }),
+                ..
+            }) => Some(doc.value()),
+            _ => None,
+        })
+        .collect()
+}
+
+// Returns the index of the category of a method in CATEGORIES.
+fn categorize(name: &str, returns_stream: bool) -> usize {
+    const SUBSCRIPTION_PREFIXES: [&str; 3] = ["subscribe_", "watch_", "monitor_"];
+    const PROOF_KEYWORDS: [&str; 5] = ["proof", "request", "fulfill", "deadline", "verifier"];
+    const ESTIMATION_KEYWORDS: [&str; 6] = ["estimat", "gas", "fee", "price", "cost", "window"];
+
+    if returns_stream || SUBSCRIPTION_PREFIXES.iter().any(|prefix| name.starts_with(prefix)) {
+        1
+    } else if PROOF_KEYWORDS.iter().any(|keyword| name.contains(keyword)) {
+        3
+    } else if ESTIMATION_KEYWORDS.iter().any(|keyword| name.contains(keyword)) {
+        2
+    } else {
+        0
+    }
+}
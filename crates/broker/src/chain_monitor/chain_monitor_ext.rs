@@ -0,0 +1,120 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Higher-level convenience methods of chain monitors, kept in an extension trait implemented
+//! for every [ChainMonitor] so that the core API stays focused on chain state.
+
+use alloy::{
+    primitives::{Address, B256},
+    rpc::types::{Filter, Log},
+    sol_types::{SolCall, SolEvent},
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+
+use super::{log_pages, ChainMonitor, IProofRequestQueue};
+
+/// Convenience methods built on top of a [ChainMonitor].
+#[async_trait]
+pub trait ChainMonitorExt: Sized {
+    /// Fetches the logs matching `filter` in the last `blocks` blocks up to the current head, in
+    /// pages of 1000 blocks.
+    ///
+    /// The block range of `filter` is replaced.
+    async fn filter_recent_logs(&self, filter: Filter, blocks: u64) -> Result<Vec<Log>>;
+
+    /// Builds a [Filter] for the event `E` emitted by `address`, using the event signature hash as
+    /// `topic0` and the cached chain head as the upper bound of the block range.
+    ///
+    /// This does not trigger a chain head update, so the range ends at the last polled block.
+    fn log_filter_from_abi<E: SolEvent>(&self, address: Address, from_block: u64) -> Filter;
+
+    /// Decodes `log` as the event `E`, checking its signature topic.
+    fn decode_log<E: SolEvent>(&self, log: &Log) -> Result<E>;
+
+    /// Returns the number of pending proof requests reported by the `pendingRequestCount()`
+    /// function of the proof-request contract at `contract`, at the current block.
+    ///
+    /// Logs a queue depth alert if the depth exceeds the configured maximum.
+    async fn request_queue_depth(&self, contract: Address) -> Result<u64>;
+
+    /// Returns a block explorer URL for a transaction hash or an address.
+    ///
+    /// 32-byte hashes link to the transaction page and 20-byte addresses to the address page;
+    /// anything else links to the explorer search.
+    fn etherscan_link(&self, hash_or_address: &str) -> String;
+}
+
+#[async_trait]
+impl<T: ChainMonitor> ChainMonitorExt for T {
+    async fn filter_recent_logs(&self, filter: Filter, blocks: u64) -> Result<Vec<Log>> {
+        let head = self.current_block_number().await?;
+        let from_block = (head + 1).saturating_sub(blocks);
+        let mut logs = Vec::new();
+        for (start, end) in log_pages(from_block, head) {
+            logs.extend(self.get_logs(&filter.clone().from_block(start).to_block(end)).await?);
+        }
+        Ok(logs)
+    }
+
+    fn log_filter_from_abi<E: SolEvent>(&self, address: Address, from_block: u64) -> Filter {
+        Filter::new()
+            .event_signature(E::SIGNATURE_HASH)
+            .address(address)
+            .from_block(from_block)
+            .to_block(self.cached_block_number())
+    }
+
+    fn decode_log<E: SolEvent>(&self, log: &Log) -> Result<E> {
+        Ok(log
+            .log_decode::<E>()
+            .with_context(|| format!("failed to decode {} log", E::SIGNATURE))?
+            .inner
+            .data)
+    }
+
+    async fn request_queue_depth(&self, contract: Address) -> Result<u64> {
+        let block_number = self.current_block_number().await?;
+        let depth = match self.recorded_queue_depth(contract, block_number).await {
+            Some(depth) => depth,
+            None => {
+                let call = IProofRequestQueue::pendingRequestCountCall {};
+                let output = self.call_at(contract, call.abi_encode().into(), block_number).await?;
+                let count =
+                    IProofRequestQueue::pendingRequestCountCall::abi_decode_returns(&output)
+                        .with_context(|| format!("failed to decode queue depth from {contract}"))?;
+                u64::try_from(count)
+                    .with_context(|| format!("queue depth of {contract} out of range: {count}"))?
+            }
+        };
+
+        self.record_queue_depth(contract, block_number, depth).await;
+        if let Some(max_depth) = self.config().max_queue_depth.filter(|max| depth > *max) {
+            tracing::warn!(
+                "[B-CHM-600] Proof request queue depth of {contract} is {depth}, above the maximum of {max_depth}"
+            );
+        }
+        Ok(depth)
+    }
+
+    fn etherscan_link(&self, hash_or_address: &str) -> String {
+        if let Ok(tx_hash) = hash_or_address.parse::<B256>() {
+            self.block_explorer_tx_url(tx_hash)
+        } else if let Ok(address) = hash_or_address.parse::<Address>() {
+            self.block_explorer_address_url(address)
+        } else {
+            format!("{}/search?q={hash_or_address}", self.explorer_base_url())
+        }
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rolling window of chain monitor errors, used to alert on sustained failures rather than on
+//! every transient RPC error.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Collects the times of the errors raised within a rolling time window.
+pub(crate) struct ErrorAggregator {
+    window: Duration,
+    max_errors: u32,
+    errors: VecDeque<Instant>,
+}
+
+impl ErrorAggregator {
+    /// Creates an aggregator whose alert threshold is exceeded when more than `max_errors`
+    /// errors were recorded within the last `window`.
+    pub(crate) fn new(window: Duration, max_errors: u32) -> Self {
+        Self { window, max_errors, errors: VecDeque::new() }
+    }
+
+    /// Records an error, dropping errors that have fallen out of the window.
+    pub(crate) fn record(&mut self) {
+        self.record_at(Instant::now());
+    }
+
+    fn record_at(&mut self, now: Instant) {
+        while self.errors.front().is_some_and(|at| now.duration_since(*at) > self.window) {
+            self.errors.pop_front();
+        }
+        self.errors.push_back(now);
+    }
+
+    /// Returns whether more than the maximum number of errors were recorded within the window.
+    pub(crate) fn is_alert_threshold_exceeded(&self) -> bool {
+        self.count_since(Instant::now()) > self.max_errors as usize
+    }
+
+    fn count_since(&self, now: Instant) -> usize {
+        self.errors.iter().filter(|at| now.duration_since(**at) <= self.window).count()
+    }
+
+    /// Clears all recorded errors.
+    pub(crate) fn reset(&mut self) {
+        self.errors.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threshold_counts_errors_within_window() {
+        let mut aggregator = ErrorAggregator::new(Duration::from_secs(60), 2);
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            aggregator.record_at(start);
+        }
+        assert_eq!(aggregator.count_since(start), 2);
+        assert!(aggregator.count_since(start) <= aggregator.max_errors as usize);
+
+        aggregator.record_at(start + Duration::from_secs(30));
+        assert_eq!(aggregator.count_since(start + Duration::from_secs(30)), 3);
+
+        // The first two errors fall out of the window.
+        let later = start + Duration::from_secs(61);
+        aggregator.record_at(later);
+        assert_eq!(aggregator.errors.len(), 2);
+        assert_eq!(aggregator.count_since(later), 2);
+    }
+
+    #[test]
+    fn reset_clears_errors() {
+        let mut aggregator = ErrorAggregator::new(Duration::from_secs(60), 0);
+        aggregator.record();
+        assert!(aggregator.is_alert_threshold_exceeded());
+
+        aggregator.reset();
+        assert!(!aggregator.is_alert_threshold_exceeded());
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! End-to-end tests of the proof request lifecycle, from request to final fulfillment, against a
+//! mock proof-request manager deployed on Anvil.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use alloy::{
+    network::{EthereumWallet, TransactionBuilder},
+    node_bindings::Anvil,
+    primitives::{keccak256, Bytes, B256, U256},
+    providers::{ext::AnvilApi, Provider, ProviderBuilder},
+    rpc::types::TransactionRequest,
+    signers::local::PrivateKeySigner,
+    sol,
+    sol_types::{SolCall, SolEvent},
+};
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    ChainMonitorConfig, ChainMonitorService, IProofPricing, IProofRequestQueue,
+    ProofFulfilledEvent, ProofRequestEvent, QueueUpdate, SubmitConfig, Urgency,
+};
+use crate::task::RetryTask;
+
+sol! {
+    interface IProofRequestManager {
+        function requestProof(uint256 id, bytes32 inputHash, uint64 deadline, uint256 maxPrice) external;
+    }
+}
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Init code of a mock proof-request manager.
+///
+/// `requestProof` emits `ProofRequested` with the caller as the requester, `fulfillRequest` emits
+/// `ProofFulfilled` with the caller as the prover and the hash of the proof, and `currentPrice`
+/// returns a proof price of 1. Any other call reverts.
+fn proof_request_manager_init_code() -> Bytes {
+    let runtime = [
+        // Dispatch on the selector, jumping to 0x27 for requestProof, 0x68 for fulfillRequest and
+        // 0xae for currentPrice.
+        &[0x60, 0x00, 0x35, 0x60, 0xe0, 0x1c, 0x80, 0x63][..],
+        &IProofRequestManager::requestProofCall::SELECTOR,
+        &[0x14, 0x60, 0x27, 0x57, 0x80, 0x63],
+        &IProofRequestQueue::fulfillRequestCall::SELECTOR,
+        &[0x14, 0x60, 0x68, 0x57, 0x63],
+        &IProofPricing::currentPriceCall::SELECTOR,
+        &[0x14, 0x60, 0xae, 0x57, 0x60, 0x00, 0x80, 0xfd],
+        // requestProof: store (caller, inputHash, deadline, maxPrice) and LOG2 it with the
+        // ProofRequested signature and the request ID as topics.
+        &[
+            0x5b, 0x33, 0x60, 0x00, 0x52, 0x60, 0x24, 0x35, 0x60, 0x20, 0x52, 0x60, 0x44, 0x35,
+            0x60, 0x40, 0x52, 0x60, 0x64, 0x35, 0x60, 0x60, 0x52, 0x60, 0x04, 0x35, 0x7f,
+        ],
+        IProofRequestQueue::ProofRequested::SIGNATURE_HASH.as_slice(),
+        &[0x60, 0x80, 0x60, 0x00, 0xa2, 0x00],
+        // fulfillRequest: hash the proof, store (caller, proofHash) and LOG2 it with the
+        // ProofFulfilled signature and the request ID as topics.
+        &[
+            0x5b, 0x60, 0x24, 0x35, 0x60, 0x04, 0x01, 0x80, 0x35, 0x90, 0x60, 0x20, 0x01, 0x81,
+            0x90, 0x60, 0x00, 0x37, 0x60, 0x00, 0x20, 0x60, 0x20, 0x52, 0x33, 0x60, 0x00, 0x52,
+            0x60, 0x04, 0x35, 0x7f,
+        ],
+        IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH.as_slice(),
+        &[0x60, 0x40, 0x60, 0x00, 0xa2, 0x00],
+        // currentPrice: return 1.
+        &[0x5b, 0x60, 0x01, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3],
+    ]
+    .concat();
+
+    let len = u8::try_from(runtime.len()).unwrap();
+    let init = [0x60, len, 0x60, 0x0c, 0x60, 0x00, 0x39, 0x60, len, 0x60, 0x00, 0xf3];
+    [&init[..], &runtime].concat().into()
+}
+
+#[tokio::test]
+async fn proof_request_lifecycle() {
+    let anvil = Anvil::new().spawn();
+    let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+    let broker = signer.address();
+    let provider = Arc::new(
+        ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect(&anvil.endpoint())
+            .await
+            .unwrap(),
+    );
+
+    let deploy_tx =
+        TransactionRequest::default().with_deploy_code(proof_request_manager_init_code());
+    let receipt = provider.send_transaction(deploy_tx).await.unwrap().get_receipt().await.unwrap();
+    let manager = receipt.contract_address.unwrap();
+
+    let chain_monitor = Arc::new(
+        ChainMonitorService::new(provider.clone())
+            .await
+            .unwrap()
+            .with_config(ChainMonitorConfig { finalization_depth: 2, ..Default::default() }),
+    );
+    tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+    let (updates_tx, mut updates) = mpsc::unbounded_channel();
+    let mut queue = Box::pin(chain_monitor.monitor_proof_request_queue(manager));
+    tokio::spawn(async move {
+        while let Some(update) = queue.next().await {
+            if updates_tx.send(update).is_err() {
+                break;
+            }
+        }
+    });
+    // Give the queue stream time to install its log filter.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    // A request is made and picked up by the queue monitor.
+    let request_id = U256::from(42);
+    let request_call = IProofRequestManager::requestProofCall {
+        id: request_id,
+        inputHash: B256::repeat_byte(0x22),
+        deadline: 1_000,
+        maxPrice: U256::from(1_000_000),
+    };
+    let request_tx =
+        TransactionRequest::default().with_to(manager).with_input(request_call.abi_encode());
+    let request_receipt =
+        provider.send_transaction(request_tx).await.unwrap().get_receipt().await.unwrap();
+
+    let update = tokio::time::timeout(TIMEOUT, updates.recv()).await.unwrap().unwrap();
+    let expected_request = ProofRequestEvent {
+        id: request_id,
+        requester: broker,
+        input_hash: request_call.inputHash,
+        deadline: request_call.deadline,
+        max_price: request_call.maxPrice,
+        block_number: request_receipt.block_number.unwrap(),
+    };
+    assert_eq!(update, QueueUpdate::NewRequest(expected_request.clone()));
+    assert_eq!(
+        chain_monitor.get_proof_request_event(manager, request_id).await.unwrap(),
+        Some(expected_request)
+    );
+
+    // The submission is simulated, then sent.
+    let proof = Bytes::from_static(b"mock proof");
+    let public_inputs = Bytes::from_static(b"mock public inputs");
+    let simulation = chain_monitor
+        .simulate_proof_submission(manager, request_id, proof.clone(), public_inputs.clone())
+        .await
+        .unwrap();
+    assert!(simulation.success, "simulation failed: {:?}", simulation.revert_reason);
+
+    let submission_tx = chain_monitor
+        .encode_proof_submission_tx(manager, request_id, proof.clone(), public_inputs)
+        .await
+        .unwrap();
+    let submission_receipt =
+        provider.send_transaction(submission_tx).await.unwrap().get_receipt().await.unwrap();
+    assert!(submission_receipt.status());
+
+    // The fulfillment is observed by the queue monitor and found by event lookup.
+    let expected_fulfillment = ProofFulfilledEvent {
+        id: request_id,
+        prover: broker,
+        proof_hash: keccak256(&proof),
+        block_number: submission_receipt.block_number.unwrap(),
+    };
+    let update = tokio::time::timeout(TIMEOUT, updates.recv()).await.unwrap().unwrap();
+    assert_eq!(update, QueueUpdate::Fulfilled(expected_fulfillment.clone()));
+    *chain_monitor.next_update.write().await = Instant::now();
+    assert_eq!(
+        chain_monitor.get_proof_fulfilled_event(manager, request_id).await.unwrap(),
+        Some(expected_fulfillment)
+    );
+
+    // The fulfillment becomes final once buried under the finalization depth.
+    provider.anvil_mine(Some(2), None).await.unwrap();
+    *chain_monitor.next_update.write().await = Instant::now();
+    let confirmations = chain_monitor.subscribe_confirmations(submission_receipt.transaction_hash);
+    let confirmations =
+        tokio::time::timeout(TIMEOUT, confirmations.collect::<Vec<_>>()).await.unwrap();
+    assert_eq!(confirmations.last(), Some(&2));
+}
+
+#[tokio::test]
+async fn submit_proof_with_retry_fulfills_request() {
+    let anvil = Anvil::new().block_time(1).spawn();
+    let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+    let broker = signer.address();
+    let provider = Arc::new(
+        ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect(&anvil.endpoint())
+            .await
+            .unwrap(),
+    );
+
+    let deploy_tx =
+        TransactionRequest::default().with_deploy_code(proof_request_manager_init_code());
+    let receipt = provider.send_transaction(deploy_tx).await.unwrap().get_receipt().await.unwrap();
+    let manager = receipt.contract_address.unwrap();
+
+    let request_id = U256::from(7);
+    let request_call = IProofRequestManager::requestProofCall {
+        id: request_id,
+        inputHash: B256::repeat_byte(0x22),
+        deadline: 1_000,
+        maxPrice: U256::from(1_000_000),
+    };
+    let request_tx =
+        TransactionRequest::default().with_to(manager).with_input(request_call.abi_encode());
+    provider.send_transaction(request_tx).await.unwrap().get_receipt().await.unwrap();
+
+    let chain_monitor = Arc::new(
+        ChainMonitorService::new(provider.clone())
+            .await
+            .unwrap()
+            .with_config(ChainMonitorConfig { finalization_depth: 2, ..Default::default() }),
+    );
+    tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+    let proof = Bytes::from_static(b"mock proof");
+    let config = SubmitConfig {
+        pricing_contract: Some(manager),
+        max_fee: U256::from(1),
+        urgency: Urgency::Medium,
+        retry_on_failure: true,
+    };
+    let tx_hash = tokio::time::timeout(
+        TIMEOUT,
+        chain_monitor.submit_proof_with_retry(
+            manager,
+            request_id,
+            proof.clone(),
+            Bytes::from_static(b"mock public inputs"),
+            config,
+        ),
+    )
+    .await
+    .unwrap()
+    .unwrap();
+
+    let receipt = provider.get_transaction_receipt(tx_hash).await.unwrap().unwrap();
+    assert!(receipt.status());
+    let fulfillment =
+        chain_monitor.get_proof_fulfilled_event(manager, request_id).await.unwrap().unwrap();
+    assert_eq!(fulfillment.prover, broker);
+    assert_eq!(fulfillment.proof_hash, keccak256(&proof));
+    assert_eq!(fulfillment.block_number, receipt.block_number.unwrap());
+}
@@ -0,0 +1,6437 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloy_chains::NamedChain;
+use either::Either;
+use futures::{
+    future::{join_all, try_join_all},
+    stream, Stream, StreamExt,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::{mpsc, watch, Notify, RwLock},
+    task::JoinHandle,
+};
+use tokio_util::sync::CancellationToken;
+
+use alloy::{
+    consensus::{Transaction as _, TxEnvelope},
+    eips::{
+        eip1559::{BaseFeeParams, Eip1559Estimation},
+        eip2718::{Decodable2718, Encodable2718},
+        eip4844::DATA_GAS_PER_BLOB,
+        BlockId, BlockNumberOrTag,
+    },
+    json_abi::Event,
+    network::{TransactionBuilder, TransactionResponse as _},
+    primitives::{address, b256, keccak256, uint, Address, Bytes, TxKind, B256, I256, U256, U64},
+    providers::Provider,
+    rlp::Encodable,
+    rpc::types::{
+        Block, BlockTransactions, Filter, Log, SyncStatus, Transaction, TransactionReceipt,
+        TransactionRequest,
+    },
+    sol,
+    sol_types::{decode_revert_reason, sol_data, SolCall, SolEvent, SolType},
+    trie::{proof::ProofRetainer, HashBuilder, Nibbles},
+};
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use moka::future::Cache;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    errors::CodedError,
+    impl_coded_debug,
+    task::{RetryRes, RetryTask, SupervisorErr},
+};
+
+mod chain_monitor_ext;
+mod error_aggregator;
+#[cfg(test)]
+mod integration_proof_tests;
+mod rpc_pool;
+mod serde_helpers;
+
+pub use chain_monitor_ext::ChainMonitorExt;
+use error_aggregator::ErrorAggregator;
+pub use rpc_pool::RpcPool;
+
+/// Max number of entries kept in each of the per-block `eth_call` result caches.
+const CALL_CACHE_SIZE: u64 = 1024;
+
+/// Max number of blocks kept in the per-block data caches.
+const BLOCK_CACHE_SIZE: u64 = 128;
+
+/// Number of recent blocks sampled when estimating block times.
+const BLOCK_TIME_SAMPLE_SIZE: u64 = 10;
+
+/// Base fee slope, in gwei per block, beyond which the base fee is considered to be trending.
+const BASE_FEE_TREND_THRESHOLD_GWEI: f64 = 2.0;
+
+/// Number of recent blocks whose tips are sampled by [ChainMonitorService::recommended_gas_price].
+const PRIORITY_FEE_SAMPLE_SIZE: u64 = 10;
+
+/// Block time assumed for chains without a known block time hint.
+const DEFAULT_BLOCK_TIME: Duration = Duration::from_secs(2);
+
+/// Default rolling window and number of poll errors within it that trigger an alert.
+const DEFAULT_ERROR_ALERT_WINDOW: Duration = Duration::from_secs(60);
+const DEFAULT_ERROR_ALERT_THRESHOLD: u32 = 10;
+
+/// Default percentage added to deployment gas estimates.
+const DEFAULT_GAS_ESTIMATE_BUFFER: u64 = 10;
+
+/// Default number of confirmations after which a block is considered final.
+const DEFAULT_FINALIZATION_DEPTH: u64 = 12;
+
+/// Delay between retries of [ChainMonitorService::broadcast_signed_tx_with_retry].
+const BROADCAST_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Percentage by which the fees of a replacement transaction must exceed those of the
+/// transaction it replaces.
+const REPLACEMENT_FEE_BUMP: u128 = 10;
+
+/// Number of attempts of [ChainMonitorService::submit_proof_with_retry] when retrying on
+/// failure.
+const MAX_SUBMISSION_ATTEMPTS: u32 = 3;
+
+/// Number of recent blocks whose base fees classify the trend of
+/// [ChainMonitorService::fee_market_summary].
+const BASE_FEE_TREND_SAMPLE_SIZE: u64 = 10;
+
+/// Number of recent blocks averaged by the gas price EMA of [ChainMonitorService::gas_tracker_report].
+const GAS_PRICE_EMA_SAMPLE_SIZE: u64 = 20;
+
+/// Number of hourly blocks sampled for the 24-hour average of
+/// [ChainMonitorService::gas_tracker_report].
+const GAS_PRICE_DAY_SAMPLES: u64 = 24;
+
+/// Max number of blocks [ChainMonitorService::gas_tracker_report] expects a transaction to wait.
+const MAX_CONFIRMATION_BLOCKS: u64 = 100;
+
+/// Default number of blocks before a request's deadline by which its proof should be submitted.
+const DEFAULT_MIN_DEADLINE_BUFFER: u64 = 10;
+
+/// Default time expected to generate a proof.
+const DEFAULT_PROOF_GENERATION_OVERHEAD: Duration = Duration::from_secs(300);
+
+/// Number of recent blocks whose base fees [ChainMonitorService::optimal_submission_window]
+/// extrapolates, and the percentile of them below which a block is considered cheap.
+const SUBMISSION_WINDOW_SAMPLE_SIZE: u64 = 50;
+const SUBMISSION_WINDOW_PERCENTILE: usize = 40;
+
+/// Number of confirmations after which [ChainMonitorService::watch_block_confirmations] ends.
+const MAX_WATCHED_BLOCK_CONFIRMATIONS: u64 = 100;
+
+/// Number of blocks covered by each `eth_getLogs` request of paginated log queries.
+const LOG_PAGE_SIZE: u64 = 1000;
+
+/// Default number of blocks searched back from the head for the events of proof requests.
+const DEFAULT_REQUEST_LOOKBACK_BLOCKS: u64 = 100_000;
+
+/// Default number of blocks a request must have been pending for before it is retried.
+const DEFAULT_PROOF_RETRY_MIN_AGE_BLOCKS: u64 = 100;
+
+/// Max number of blocks a compared provider may lag or lead the primary provider by.
+const PROVIDER_AGREEMENT_BLOCKS: u64 = 2;
+
+/// Max number of transaction receipts kept in the receipt cache.
+const RECEIPT_CACHE_SIZE: u64 = 1024;
+
+/// Time for which pending nonces are cached, as they can change between blocks.
+const PENDING_NONCE_TTL: Duration = Duration::from_millis(100);
+
+/// Interval at which the sync status is polled on providers without subscription support.
+const SYNC_STATUS_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Canonical Multicall3 deployment address, shared by most EVM chains.
+const MULTICALL3_ADDRESS: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// Static PoW block rewards, in wei, and the Ethereum mainnet blocks they were activated at.
+const FRONTIER_BLOCK_REWARD: U256 = uint!(5_000_000_000_000_000_000_U256);
+const BYZANTIUM_BLOCK_REWARD: U256 = uint!(3_000_000_000_000_000_000_U256);
+const CONSTANTINOPLE_BLOCK_REWARD: U256 = uint!(2_000_000_000_000_000_000_U256);
+const MAINNET_BYZANTIUM_BLOCK: u64 = 4_370_000;
+const MAINNET_CONSTANTINOPLE_BLOCK: u64 = 7_280_000;
+
+/// Gas consumed by a plain ETH transfer, which is the base cost of every transaction.
+const SIMPLE_TRANSFER_GAS: u64 = 21_000;
+
+/// Additional base cost of a contract creation transaction.
+const CONTRACT_CREATION_GAS: u64 = 32_000;
+
+/// Max share of the gas used that can be refunded, as `gas_used / MAX_REFUND_QUOTIENT` (EIP-3529).
+const MAX_REFUND_QUOTIENT: u64 = 5;
+
+/// Refund assumed per pre-warmed storage slot of a transaction's access list.
+const ACCESS_LIST_SLOT_REFUND_GAS: u64 = 2400;
+
+/// Calldata cost per zero and non-zero byte (EIP-2028).
+const ZERO_BYTE_GAS: u64 = 4;
+const NON_ZERO_BYTE_GAS: u64 = 16;
+
+/// Max blobs per block from Cancun (EIP-4844) and Prague (EIP-7691) onwards.
+const CANCUN_MAX_BLOB_COUNT: u8 = 6;
+const PRAGUE_MAX_BLOB_COUNT: u8 = 9;
+
+/// Timestamps at which Prague was activated on Ethereum mainnet, Sepolia and Holesky.
+const MAINNET_PRAGUE_TIMESTAMP: u64 = 1_746_612_311;
+const SEPOLIA_PRAGUE_TIMESTAMP: u64 = 1_741_159_776;
+const HOLESKY_PRAGUE_TIMESTAMP: u64 = 1_740_434_112;
+
+/// Fee recipients of the Flashbots and builder0x69 block builders on Ethereum mainnet.
+const FLASHBOTS_BUILDER_ADDRESSES: [Address; 2] = [
+    address!("dafea492d9c6733ae3d56b7ed1adb60692c98bc5"),
+    address!("690b9a9e9aa1c9db991c7721a92d351db4fac990"),
+];
+
+/// Extra data markers set by the Flashbots and builder0x69 block builders.
+const FLASHBOTS_EXTRA_DATA_MARKERS: [&[u8]; 2] = [b"Flashbots", b"builder0x69"];
+
+/// Etherscan multichain API endpoint, selecting the chain with the `chainid` query parameter.
+const DEFAULT_ETHERSCAN_API_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Selector of the `paused()` function of OpenZeppelin `Pausable` contracts.
+const PAUSE_SELECTOR: [u8; 4] = [0x5c, 0x97, 0x5a, 0xbb];
+
+/// EIP-1967 implementation slot, `keccak256("eip1967.proxy.implementation") - 1`.
+const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// Legacy ZeppelinOS implementation slot, `keccak256("org.zeppelinos.proxy.implementation")`.
+/// This predates EIP-1967 and, unlike it, is not offset by one.
+const ZEPPELINOS_IMPLEMENTATION_SLOT: B256 =
+    b256!("7050c9e0f4ca769c69bd3a8ef740bc37934f8e2c036e5a723fd8ee048ed3f8c3");
+
+sol! {
+    #[sol(rpc)]
+    interface IAggregatorV3 {
+        function decimals() external view returns (uint8);
+        function latestAnswer() external view returns (int256);
+        function latestRoundData() external view returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound);
+    }
+
+    interface IERC165 {
+        function supportsInterface(bytes4 interfaceId) external view returns (bool);
+    }
+
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+    }
+
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function allowance(address owner, address spender) external view returns (uint256);
+
+        event Transfer(address indexed from, address indexed to, uint256 value);
+    }
+
+    interface IProofPricing {
+        function currentPrice() external view returns (uint256);
+    }
+
+    interface IProofVerifier {
+        function verify(bytes proof, bytes publicInputs) external view returns (bool);
+    }
+
+    interface IProofRequestQueue {
+        struct ProofRequest {
+            address requester;
+            bytes32 inputHash;
+            uint64 deadline;
+            uint256 maxPrice;
+        }
+
+        function pendingRequestCount() external view returns (uint256);
+        function fulfillRequest(uint256 id, bytes proof, bytes publicInputs) external;
+        function getRequest(uint256 id) external view returns (ProofRequest memory);
+        function getRequestInputHash(uint256 id) external view returns (bytes32);
+
+        event ProofRequested(uint256 indexed id, address requester, bytes32 inputHash, uint64 deadline, uint256 maxPrice);
+        event ProofFulfilled(uint256 indexed id, address prover, bytes32 proofHash);
+    }
+}
+
+#[derive(Error)]
+pub enum ChainMonitorErr {
+    #[error("{code} RPC error: {0:?}", code = self.code())]
+    RpcErr(anyhow::Error),
+    #[error("{code} Not an Arbitrum chain", code = self.code())]
+    NotArbitrumChain,
+    #[error("{code} Gas price of {current} wei exceeds the maximum of {max_allowed} wei", code = self.code())]
+    GasPriceTooHigh { current: u128, max_allowed: u128 },
+    #[error("{code} Transaction {tx_hash} is underpriced and must be re-signed with a max fee per gas of at least {min_max_fee_per_gas} wei", code = self.code())]
+    RequiresResign { tx_hash: B256, min_max_fee_per_gas: u128 },
+    #[error("{code} Timed out after {0:?}", code = self.code())]
+    Timeout(Duration),
+    #[error("{code} Unexpected error: {0:?}", code = self.code())]
+    UnexpectedErr(#[from] anyhow::Error),
+}
+
+impl_coded_debug!(ChainMonitorErr);
+
+impl CodedError for ChainMonitorErr {
+    fn code(&self) -> &str {
+        match self {
+            ChainMonitorErr::RpcErr(_) => "[B-CHM-400]",
+            ChainMonitorErr::NotArbitrumChain => "[B-CHM-001]",
+            ChainMonitorErr::GasPriceTooHigh { .. } => "[B-CHM-002]",
+            ChainMonitorErr::RequiresResign { .. } => "[B-CHM-003]",
+            ChainMonitorErr::Timeout(_) => "[B-CHM-004]",
+            ChainMonitorErr::UnexpectedErr(_) => "[B-CHM-500]",
+        }
+    }
+}
+
+/// Summary of a block header, as tracked by the [ChainMonitorService].
+#[derive(Clone, Debug, Copy, Default, PartialEq, Eq)]
+pub struct ChainHead {
+    pub block_number: u64,
+    pub block_timestamp: u64,
+    pub block_hash: B256,
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// L1 block number the block was produced from, as reported by Arbitrum nodes. Only
+    /// populated for heads fetched through the Arbitrum-specific RPC path.
+    pub l1_block_number: Option<u64>,
+    /// Root of the withdrawals trie, present from Shanghai (EIP-4895) onwards.
+    pub withdrawals_root: Option<B256>,
+    /// Whether the block processed any beacon chain withdrawals.
+    pub has_withdrawals: bool,
+    /// EIP-1559 base fee of the block, absent on pre-London chains.
+    pub base_fee_per_gas: Option<u128>,
+    /// Root of the state trie after executing the block.
+    pub state_root: B256,
+    /// Root of the trie of the block's transactions.
+    pub transactions_root: B256,
+    /// Root of the trie of the block's transaction receipts.
+    pub receipts_root: B256,
+    /// PoW difficulty of the block, absent on PoS blocks.
+    pub difficulty: Option<U256>,
+    /// PoW mix hash of the block, absent on PoS blocks where the field holds `prevRandao`.
+    pub mix_hash: Option<B256>,
+}
+
+impl ChainHead {
+    fn from_block(block: &Block) -> Self {
+        let is_pow = !block.header.difficulty.is_zero();
+        Self {
+            block_number: block.header.number,
+            block_timestamp: block.header.timestamp,
+            block_hash: block.header.hash,
+            parent_hash: block.header.parent_hash,
+            l1_block_number: None,
+            withdrawals_root: block.header.withdrawals_root,
+            has_withdrawals: block.withdrawals.as_ref().is_some_and(|w| !w.is_empty()),
+            base_fee_per_gas: block.header.base_fee_per_gas.map(u128::from),
+            state_root: block.header.state_root,
+            transactions_root: block.header.transactions_root,
+            receipts_root: block.header.receipts_root,
+            difficulty: is_pow.then_some(block.header.difficulty),
+            mix_hash: is_pow.then_some(block.header.mix_hash),
+        }
+    }
+}
+
+/// Subset of the Arbitrum block header, which extends the Ethereum header with the L1 block
+/// number.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ArbitrumHeader {
+    number: U64,
+    timestamp: U64,
+    hash: B256,
+    l1_block_number: U64,
+}
+
+/// Merkle proof of a single storage slot against the storage root of an account.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageProof {
+    /// Storage slot the proof was generated for.
+    pub key: U256,
+    /// Value held in the slot.
+    pub value: U256,
+    /// RLP-encoded trie nodes from the storage root to the slot.
+    pub proof: Vec<Bytes>,
+}
+
+/// Mining rewards paid to the miner of a block, in wei.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockRewards {
+    /// Static block reward.
+    pub base_reward: U256,
+    /// Reward for including each of the block's uncles.
+    pub uncle_inclusion_rewards: Vec<U256>,
+    /// Sum of the base and uncle inclusion rewards.
+    pub total: U256,
+}
+
+impl BlockRewards {
+    /// Rewards of a block with no mining rewards, such as a PoS block.
+    pub fn zero() -> Self {
+        Self::default()
+    }
+}
+
+/// A block with its full transactions and their receipts, in block order.
+#[derive(Clone, Debug)]
+pub struct BlockWithReceipts {
+    pub head: ChainHead,
+    pub transactions: Vec<Transaction>,
+    pub receipts: Vec<TransactionReceipt>,
+}
+
+/// A decoded ERC-20 `Transfer` event.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ERC20TransferEvent {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub block_number: u64,
+    pub tx_hash: B256,
+}
+
+/// A decoded `ProofRequested` event of a proof-request contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofRequestEvent {
+    pub id: U256,
+    pub requester: Address,
+    pub input_hash: B256,
+    /// Block number by which the request must be fulfilled.
+    pub deadline: u64,
+    pub max_price: U256,
+    /// Block the request was made in.
+    pub block_number: u64,
+}
+
+impl ProofRequestEvent {
+    fn new(event: IProofRequestQueue::ProofRequested, block_number: u64) -> Self {
+        Self {
+            id: event.id,
+            requester: event.requester,
+            input_hash: event.inputHash,
+            deadline: event.deadline,
+            max_price: event.maxPrice,
+            block_number,
+        }
+    }
+}
+
+/// A decoded `ProofFulfilled` event of a proof-request contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofFulfilledEvent {
+    pub id: U256,
+    pub prover: Address,
+    pub proof_hash: B256,
+    /// Block the request was fulfilled in.
+    pub block_number: u64,
+}
+
+impl ProofFulfilledEvent {
+    fn new(event: IProofRequestQueue::ProofFulfilled, block_number: u64) -> Self {
+        Self { id: event.id, prover: event.prover, proof_hash: event.proofHash, block_number }
+    }
+}
+
+/// Replacement of the chain head by a block of another fork, yielded by
+/// [ChainMonitorService::subscribe_reorgs].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReorgEvent {
+    /// Last observed head of the abandoned fork.
+    pub old_head: ChainHead,
+    /// First observed head of the new canonical fork.
+    pub new_head: ChainHead,
+}
+
+/// Proof request whose deadline is approaching, yielded by
+/// [ChainMonitorService::watch_proof_deadlines].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeadlineWarning {
+    pub request_id: U256,
+    pub deadline_block: u64,
+    /// Number of blocks from the chain head to the deadline.
+    pub blocks_remaining: u64,
+}
+
+/// Unfulfilled proof request scheduled for re-submission by
+/// [ChainMonitorService::retry_pending_proofs].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProofRetry {
+    pub contract: Address,
+    pub request_id: U256,
+    pub deadline_block: u64,
+}
+
+/// Change to the queue of a proof-request contract, yielded by
+/// [ChainMonitorService::monitor_proof_request_queue].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueueUpdate {
+    NewRequest(ProofRequestEvent),
+    Fulfilled(ProofFulfilledEvent),
+    /// A request seen by the stream passed its deadline without being fulfilled.
+    Expired {
+        id: U256,
+    },
+}
+
+impl QueueUpdate {
+    /// Decodes a `ProofRequested` or `ProofFulfilled` log.
+    fn from_log(log: &Log) -> Result<Self> {
+        let block_number = log.block_number.context("queue log is missing its block")?;
+        let topic0 = log.topic0().copied();
+        if topic0 == Some(IProofRequestQueue::ProofRequested::SIGNATURE_HASH) {
+            let event = log
+                .log_decode::<IProofRequestQueue::ProofRequested>()
+                .context("failed to decode ProofRequested log")?;
+            Ok(Self::NewRequest(ProofRequestEvent::new(event.inner.data, block_number)))
+        } else if topic0 == Some(IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH) {
+            let event = log
+                .log_decode::<IProofRequestQueue::ProofFulfilled>()
+                .context("failed to decode ProofFulfilled log")?;
+            Ok(Self::Fulfilled(ProofFulfilledEvent::new(event.inner.data, block_number)))
+        } else {
+            bail!("unexpected queue log with topic0 {topic0:?}")
+        }
+    }
+}
+
+/// Position of a log consumer in the chain, persisted so that processing can resume after a
+/// restart.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentCursor {
+    pub last_processed_block: u64,
+}
+
+impl PersistentCursor {
+    /// Loads a cursor from `path`, starting from block 0 if the file does not exist.
+    pub async fn load(path: &Path) -> Result<Self> {
+        match tokio::fs::read(path).await {
+            Ok(data) => serde_json::from_slice(&data)
+                .with_context(|| format!("failed to parse cursor {}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => {
+                Err(err).with_context(|| format!("failed to read cursor {}", path.display()))
+            }
+        }
+    }
+
+    /// Writes the cursor to `path`.
+    pub async fn save(&self, path: &Path) -> Result<()> {
+        tokio::fs::write(path, serde_json::to_vec(self)?)
+            .await
+            .with_context(|| format!("failed to write cursor {}", path.display()))
+    }
+}
+
+/// Outcome of a single transaction in an `eth_callBundle` simulation.
+#[cfg(feature = "flashbots")]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResult {
+    pub tx_hash: B256,
+    pub gas_used: u64,
+    /// Return data of the call, if it succeeded.
+    #[serde(default)]
+    pub value: Option<Bytes>,
+    /// Execution error, if the call failed.
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Revert reason, if the call reverted.
+    #[serde(default)]
+    pub revert: Option<String>,
+}
+
+/// Estimated cost of a proof submission, see
+/// [ChainMonitorService::proof_submission_cost_estimate].
+#[derive(Clone, Debug, PartialEq)]
+pub struct CostEstimate {
+    pub gas_used: u64,
+    pub max_fee_per_gas: u128,
+    pub priority_fee: u128,
+    /// Upper bound of the cost, paying the max fee for all of the gas.
+    pub total_cost_wei: U256,
+    /// Total cost in USD, if an ETH/USD price feed is configured.
+    pub total_cost_usd: Option<f64>,
+}
+
+/// Snapshot of the gas market, see [ChainMonitorService::gas_tracker_report].
+///
+/// All fees are in wei.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasTrackerReport {
+    /// Base fee of the current head, absent on pre-London chains.
+    pub current_base_fee: Option<u128>,
+    pub current_gas_price: u128,
+    /// Exponential moving average of the base fee over recent blocks, or the current gas price on
+    /// chains without a base fee.
+    pub gas_price_ema: u128,
+    /// Mean base fee of hourly samples over the last 24 hours, if available.
+    pub gas_price_24h_avg: Option<u128>,
+    /// Median and 75th percentile of the priority fees paid in recent blocks.
+    pub priority_fee_p50: u128,
+    pub priority_fee_p75: u128,
+    /// Blob base fee, absent on pre-Cancun chains.
+    pub blob_base_fee: Option<u128>,
+    /// Percentage of the gas limit of the current head that was used.
+    pub chain_util_pct: f64,
+    /// Number of blocks a transaction paying the current gas price is expected to wait for
+    /// inclusion.
+    pub estimated_confirmation_blocks: u64,
+}
+
+/// Fee market signals at the current head, see [ChainMonitorService::fee_market_summary].
+///
+/// All fees are in wei.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeeMarketSummary {
+    /// Gas price reported by `eth_gasPrice`.
+    pub legacy_gas_price: u128,
+    /// Base fee of the current head, absent on pre-London chains.
+    pub base_fee: Option<u128>,
+    /// Percentiles of the priority fees paid in recent blocks.
+    pub priority_fee_p50: u128,
+    pub priority_fee_p75: u128,
+    pub priority_fee_p95: u128,
+    /// Blob base fee, absent on pre-Cancun chains.
+    pub blob_base_fee: Option<u128>,
+    /// Share of the gas limit of the current head that was used, from 0 to 1.
+    pub gas_utilization: f64,
+    pub base_fee_trend: BaseFeeTrend,
+    /// Base fee of the next block, derived from the current head with the Ethereum mainnet
+    /// EIP-1559 parameters.
+    pub next_base_fee_estimate: Option<u128>,
+}
+
+/// Options of [ChainMonitorService::submit_proof_with_retry].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SubmitConfig {
+    /// Pricing contract whose proof price is waited on before submitting, if any
+    pub pricing_contract: Option<Address>,
+    /// Highest proof price, as reported by the pricing contract, to submit at
+    pub max_fee: U256,
+    /// Urgency the submission fees are recommended for
+    pub urgency: Urgency,
+    /// Whether to retry the steps before the submission is sent when one of them fails
+    pub retry_on_failure: bool,
+}
+
+/// Outcome of a dry run of a proof submission, see
+/// [ChainMonitorService::simulate_proof_submission].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofSimulationResult {
+    pub success: bool,
+    /// Estimated gas of the submission, 0 if the simulation failed.
+    pub gas_estimate: u64,
+    /// Reason the simulation failed, if it did.
+    pub revert_reason: Option<String>,
+}
+
+/// Call frame of a transaction, as reported by the `callTracer` of the `debug_trace*` methods.
+#[cfg(feature = "debug-rpc")]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct CallTrace {
+    pub from: Address,
+    /// Callee, absent for contract creations that failed.
+    #[serde(default)]
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub value: Option<U256>,
+    pub gas: U256,
+    #[serde(default)]
+    pub output: Option<Bytes>,
+    /// Nested calls made by this frame.
+    #[serde(default)]
+    pub calls: Vec<CallTrace>,
+}
+
+/// Benchmark of an RPC endpoint against the primary provider of the [ChainMonitorService].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProviderComparison {
+    pub url: String,
+    /// Time taken to answer the chain ID and block number requests, sent concurrently.
+    pub latency_ms: u64,
+    pub chain_id: u64,
+    pub block_number: u64,
+    /// Whether the provider serves the same chain as the primary provider, with a head within
+    /// two blocks of it.
+    pub agrees_with_primary: bool,
+}
+
+/// Merkle proof of the inclusion of a transaction receipt, and thus of its logs, in the receipts
+/// trie of its block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogInclusionProof {
+    /// RLP-encoded trie nodes from the receipts root to the receipt.
+    pub receipt_proof: Vec<Bytes>,
+    /// Index of the log in the block.
+    pub log_index: u64,
+    /// Index of the receipt in the block, which is the key of the receipt in the trie.
+    pub receipt_index: u64,
+}
+
+/// Next nonce to assign for an address, `None` until initialized from the pending nonce.
+type NonceCounter = Arc<std::sync::Mutex<Option<u64>>>;
+
+/// A nonce reserved by [ChainMonitorService::nonce_safe_transfer].
+///
+/// Dropping the guard without calling [NonceGuard::commit] releases the nonce, provided no later
+/// nonce has been reserved for the address since.
+#[derive(Debug)]
+pub struct NonceGuard {
+    address: Address,
+    nonce: u64,
+    counter: NonceCounter,
+    committed: bool,
+}
+
+impl NonceGuard {
+    /// Returns the reserved nonce.
+    pub fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    /// Marks the nonce as used by a submitted transaction, so that it is not released on drop.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+}
+
+impl Drop for NonceGuard {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        let mut next = self.counter.lock().unwrap();
+        if *next == Some(self.nonce + 1) {
+            *next = Some(self.nonce);
+        } else {
+            tracing::warn!(
+                "Nonce {} of {} released after later nonces were reserved, leaving a gap",
+                self.nonce,
+                self.address
+            );
+        }
+    }
+}
+
+/// Family of the monitored chain, used to route chain-specific behaviour.
+///
+/// Testnets are classified with the network they mirror, e.g. Sepolia as [ChainType::Ethereum].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChainType {
+    Ethereum,
+    ArbitrumOne,
+    ArbitrumNova,
+    OptimismMainnet,
+    Base,
+    Polygon,
+    Other(u64),
+}
+
+impl ChainType {
+    /// Returns whether the chain is built on the Arbitrum Nitro stack.
+    pub fn is_arbitrum(self) -> bool {
+        matches!(self, Self::ArbitrumOne | Self::ArbitrumNova)
+    }
+}
+
+impl From<u64> for ChainType {
+    fn from(chain_id: u64) -> Self {
+        match NamedChain::try_from(chain_id) {
+            Ok(NamedChain::Mainnet | NamedChain::Sepolia | NamedChain::Holesky) => Self::Ethereum,
+            Ok(NamedChain::Arbitrum | NamedChain::ArbitrumSepolia) => Self::ArbitrumOne,
+            Ok(NamedChain::ArbitrumNova) => Self::ArbitrumNova,
+            Ok(NamedChain::Optimism | NamedChain::OptimismSepolia) => Self::OptimismMainnet,
+            Ok(NamedChain::Base | NamedChain::BaseSepolia) => Self::Base,
+            Ok(NamedChain::Polygon | NamedChain::PolygonAmoy) => Self::Polygon,
+            _ => Self::Other(chain_id),
+        }
+    }
+}
+
+/// Snapshot of the chain state cached by the [ChainMonitorService].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainMonitorStatus {
+    pub chain_id: u64,
+    pub head: ChainHead,
+    pub gas_price: u128,
+    /// Proof-request queue depth last observed by [ChainMonitorExt::request_queue_depth].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_depth: Option<u64>,
+    /// Whether the node was listening for peers at the last [ChainMonitorService::net_listening]
+    /// check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub net_listening: Option<bool>,
+    /// Ratio of fulfilled to made proof requests computed by the last
+    /// [ChainMonitorService::fulfillment_rate] call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fulfillment_rate: Option<f64>,
+}
+
+/// Direction of the base fee over a window of blocks, with the least-squares slope in gwei per
+/// block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BaseFeeTrend {
+    Rising(f64),
+    Falling(f64),
+    Stable,
+}
+
+impl BaseFeeTrend {
+    /// Classifies a base fee slope, in gwei per block.
+    fn from_slope(slope: f64) -> Self {
+        if slope > BASE_FEE_TREND_THRESHOLD_GWEI {
+            Self::Rising(slope)
+        } else if slope < -BASE_FEE_TREND_THRESHOLD_GWEI {
+            Self::Falling(slope)
+        } else {
+            Self::Stable
+        }
+    }
+}
+
+/// Transaction types supported by the monitored chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TxTypeSupport {
+    /// Type 0 transactions, supported by every chain.
+    pub legacy: bool,
+    /// Type 1 access list transactions (EIP-2930).
+    pub eip2930: bool,
+    /// Type 2 dynamic fee transactions (EIP-1559).
+    pub eip1559: bool,
+    /// Type 3 blob transactions (EIP-4844).
+    pub eip4844: bool,
+}
+
+impl TxTypeSupport {
+    /// Derives the supported transaction types from the hardfork fields present in `header`.
+    ///
+    /// A base fee indicates London, which follows Berlin and thus EIP-2930, and a `blobGasUsed`
+    /// field indicates Cancun.
+    fn from_header(header: &alloy::consensus::Header) -> Self {
+        let london = header.base_fee_per_gas.is_some();
+        Self {
+            legacy: true,
+            eip2930: london,
+            eip1559: london,
+            eip4844: header.blob_gas_used.is_some(),
+        }
+    }
+}
+
+/// Urgency of a transaction, determining how much base fee growth its max fee can absorb.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Urgency {
+    /// Covers the base fee of the next block.
+    Low,
+    /// Covers 3 consecutive full blocks.
+    Medium,
+    /// Covers 6 consecutive full blocks.
+    High,
+}
+
+impl Urgency {
+    /// Returns the highest base fee reachable from `base_fee` within the blocks covered by the
+    /// urgency, with each full block raising the base fee by 12.5% (EIP-1559).
+    fn max_base_fee(self, base_fee: u128) -> u128 {
+        let blocks = match self {
+            Self::Low => 1,
+            Self::Medium => 3,
+            Self::High => 6,
+        };
+        (0..blocks).fold(base_fee, |fee, _| fee.saturating_add(fee / 8))
+    }
+}
+
+/// Alert emitted by [ChainMonitorService::watch_balance_with_threshold].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BalanceAlert {
+    /// The balance changed to a value below the configured minimum.
+    BelowThreshold(U256),
+}
+
+/// Configuration for the [ChainMonitorService].
+#[derive(Clone, Debug)]
+pub struct ChainMonitorConfig {
+    /// Address of a Chainlink ETH/USD price feed on the monitored chain
+    ///
+    /// When set, [ChainMonitorService::eth_usd_price] reads the exchange rate from the feed.
+    pub eth_usd_price_feed: Option<Address>,
+    /// Number of confirmations after which a block is considered final
+    pub finalization_depth: u64,
+    /// Base URL of the Etherscan-compatible block explorer API
+    pub etherscan_api_url: String,
+    /// Block explorer API key
+    ///
+    /// Falls back to the `ETHERSCAN_API_KEY` environment variable when unset.
+    pub etherscan_api_key: Option<String>,
+    /// Base URL of the block explorer used for links in logs
+    ///
+    /// Defaults to the known explorer of the monitored chain. Links are relative paths on chains
+    /// without a known explorer.
+    pub chain_explorer_url: Option<String>,
+    /// Proof-request queue depth above which a queue depth alert is logged
+    pub max_queue_depth: Option<u64>,
+    /// Rolling window over which chain head poll errors are counted
+    pub error_alert_window: Duration,
+    /// Number of poll errors within the window above which an alert is logged
+    pub error_alert_threshold: u32,
+    /// Percentage added to deployment gas estimates
+    pub gas_estimate_buffer: u64,
+    /// Number of blocks before a request's deadline by which its proof should be submitted, so
+    /// that the submission lands in time
+    pub min_deadline_buffer: u64,
+    /// Time expected to generate a proof, added to the latency estimated by
+    /// [ChainMonitorService::estimate_proof_latency]
+    pub proof_generation_overhead: Duration,
+    /// Number of blocks searched back from the head for the events of proof requests
+    pub request_lookback_blocks: u64,
+    /// Number of blocks a request must have been pending for before
+    /// [ChainMonitorService::retry_pending_proofs] re-submits it, so that requests still being
+    /// proven are not submitted twice
+    pub proof_retry_min_age_blocks: u64,
+    /// Max fee per gas, in wei, that [ChainMonitorService::max_proof_price_for_budget] never
+    /// exceeds
+    pub absolute_max_fee_per_gas: Option<u128>,
+    /// Whether to fetch the latest block with its full transactions when polling the chain head
+    ///
+    /// When set, [ChainMonitorService::current_block_transactions] can return full transactions
+    /// without an extra RPC call.
+    pub cache_full_block: bool,
+    /// File the cursor of [ChainMonitorService::get_logs_since_last_run] is persisted to
+    pub cursor_path: Option<PathBuf>,
+    /// Directory searched for Hardhat/Foundry artifacts by
+    /// [ChainMonitorService::contract_storage_layout]
+    pub storage_layout_dir: Option<PathBuf>,
+}
+
+impl Default for ChainMonitorConfig {
+    fn default() -> Self {
+        Self {
+            eth_usd_price_feed: None,
+            finalization_depth: DEFAULT_FINALIZATION_DEPTH,
+            etherscan_api_url: DEFAULT_ETHERSCAN_API_URL.to_string(),
+            etherscan_api_key: None,
+            chain_explorer_url: None,
+            max_queue_depth: None,
+            error_alert_window: DEFAULT_ERROR_ALERT_WINDOW,
+            error_alert_threshold: DEFAULT_ERROR_ALERT_THRESHOLD,
+            gas_estimate_buffer: DEFAULT_GAS_ESTIMATE_BUFFER,
+            min_deadline_buffer: DEFAULT_MIN_DEADLINE_BUFFER,
+            proof_generation_overhead: DEFAULT_PROOF_GENERATION_OVERHEAD,
+            request_lookback_blocks: DEFAULT_REQUEST_LOOKBACK_BLOCKS,
+            proof_retry_min_age_blocks: DEFAULT_PROOF_RETRY_MIN_AGE_BLOCKS,
+            absolute_max_fee_per_gas: None,
+            cache_full_block: false,
+            cursor_path: None,
+            storage_layout_dir: None,
+        }
+    }
+}
+
+/// Core chain state of a chain monitor, on which the convenience methods of [ChainMonitorExt]
+/// are built.
+#[async_trait]
+pub trait ChainMonitor: Send + Sync {
+    /// Returns the latest block number, triggering an update if enough time has passed
+    async fn current_block_number(&self) -> Result<u64>;
+
+    /// Returns the number of the last polled block, without triggering an update.
+    fn cached_block_number(&self) -> u64;
+
+    /// Executes an `eth_call` to `to` at `block_number`.
+    async fn call_at(&self, to: Address, input: Bytes, block_number: u64) -> Result<Bytes>;
+
+    /// Fetches the logs matching `filter` in a single request.
+    async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>>;
+
+    /// Returns the configuration of the monitor.
+    fn config(&self) -> &ChainMonitorConfig;
+
+    /// Returns the base URL of the block explorer of the chain, without a trailing slash, or an
+    /// empty string if the chain has no known explorer.
+    fn explorer_base_url(&self) -> &str;
+
+    /// Returns the block explorer URL of a transaction.
+    fn block_explorer_tx_url(&self, tx_hash: B256) -> String;
+
+    /// Returns the block explorer URL of an address.
+    fn block_explorer_address_url(&self, address: Address) -> String;
+
+    /// Returns the proof-request queue depth of `contract` recorded at `block_number`, if any.
+    async fn recorded_queue_depth(&self, contract: Address, block_number: u64) -> Option<u64>;
+
+    /// Records the proof-request queue depth of `contract` at `block_number`, reporting it in
+    /// [ChainMonitorStatus::queue_depth].
+    async fn record_queue_depth(&self, contract: Address, block_number: u64, depth: u64);
+}
+
+#[derive(Clone)]
+pub struct ChainMonitorService<P> {
+    provider: Arc<P>,
+    chain_id: u64,
+    config: ChainMonitorConfig,
+    gas_price: watch::Sender<u128>,
+    update_notifier: Arc<Notify>,
+    next_update: Arc<RwLock<Instant>>,
+    head_update: watch::Sender<ChainHead>,
+    latest_block: watch::Sender<Option<Arc<Block>>>,
+    shutdown: CancellationToken,
+    dominant_builder: watch::Sender<Option<Address>>,
+    syncing: watch::Sender<bool>,
+    net_listening: watch::Sender<Option<bool>>,
+    rpc_latency: watch::Sender<Option<Duration>>,
+    queue_depth: watch::Sender<Option<u64>>,
+    fulfillment_rate: watch::Sender<Option<f64>>,
+    paused_cache: Arc<Cache<(Address, u64), bool>>,
+    storage_proof_cache: Arc<Cache<(Address, U256, u64), StorageProof>>,
+    block_receipts_cache: Arc<Cache<u64, BlockWithReceipts>>,
+    block_hash_cache: Arc<Cache<B256, ChainHead>>,
+    header_rlp_cache: Arc<Cache<u64, Bytes>>,
+    state_root_cache: Arc<Cache<u64, B256>>,
+    block_head_cache: Arc<Cache<u64, ChainHead>>,
+    interface_cache: Arc<Cache<(Address, [u8; 4]), bool>>,
+    receipt_cache: Arc<Cache<B256, TransactionReceipt>>,
+    erc20_cache: Arc<Cache<(Address, Bytes, u64), U256>>,
+    queue_depth_cache: Arc<Cache<(Address, u64), u64>>,
+    pending_nonce_cache: Arc<Cache<Address, u64>>,
+    nonce_cache: Arc<Cache<(Address, u64), u64>>,
+    nonce_counters: Arc<std::sync::Mutex<HashMap<Address, NonceCounter>>>,
+    storage_cache: Arc<Cache<(Address, U256, u64), U256>>,
+    proof_request_cache: Arc<Cache<(Address, U256), ProofRequestEvent>>,
+    proof_fulfilled_cache: Arc<Cache<(Address, U256), ProofFulfilledEvent>>,
+    block_fulfillment_cache: Arc<Cache<(Address, u64, U256), bool>>,
+    deadline_cache: Arc<Cache<(Address, U256), u64>>,
+    input_hash_cache: Arc<Cache<(Address, U256, u64), B256>>,
+    /// Deadlines of the requests looked up through the service that are not known to be
+    /// fulfilled and have not passed, per proof-request contract and request ID.
+    active_deadlines: Arc<std::sync::Mutex<HashMap<(Address, U256), u64>>>,
+    proof_price_cache: Arc<Cache<(Address, u64), U256>>,
+    /// Queue that [ChainMonitorService::retry_pending_proofs] schedules re-submissions on.
+    proof_retry_tx: Option<mpsc::Sender<ProofRetry>>,
+    /// Poll errors of the chain head task, kept across its restarts to alert on sustained
+    /// failures.
+    poll_errors: Arc<std::sync::Mutex<ErrorAggregator>>,
+    #[cfg(feature = "etherscan")]
+    abi_cache: Arc<Cache<Address, String>>,
+    #[cfg(feature = "debug-rpc")]
+    block_trace_cache: Arc<Cache<u64, Vec<CallTrace>>>,
+}
+
+impl<P: Provider> ChainMonitorService<P> {
+    /// Creates a chain monitor over `provider`, fetching the chain ID.
+    pub async fn new(provider: Arc<P>) -> Result<Self> {
+        let (gas_price, _) = watch::channel(0);
+        let (head_update, _) = watch::channel(ChainHead::default());
+        let (latest_block, _) = watch::channel(None);
+        let (dominant_builder, _) = watch::channel(None);
+        let (syncing, _) = watch::channel(false);
+        let (net_listening, _) = watch::channel(None);
+        let (rpc_latency, _) = watch::channel(None);
+        let (queue_depth, _) = watch::channel(None);
+        let (fulfillment_rate, _) = watch::channel(None);
+        let chain_id = provider.get_chain_id().await.context("failed to get chain ID")?;
+        let config = ChainMonitorConfig::default();
+
+        Ok(Self {
+            provider,
+            chain_id,
+            poll_errors: Arc::new(std::sync::Mutex::new(ErrorAggregator::new(
+                config.error_alert_window,
+                config.error_alert_threshold,
+            ))),
+            config,
+            gas_price,
+            update_notifier: Arc::new(Notify::new()),
+            next_update: Arc::new(RwLock::new(Instant::now())),
+            head_update,
+            latest_block,
+            shutdown: CancellationToken::new(),
+            dominant_builder,
+            syncing,
+            net_listening,
+            rpc_latency,
+            queue_depth,
+            fulfillment_rate,
+            paused_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            storage_proof_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            block_receipts_cache: Arc::new(Cache::new(BLOCK_CACHE_SIZE)),
+            block_hash_cache: Arc::new(Cache::new(BLOCK_CACHE_SIZE)),
+            header_rlp_cache: Arc::new(Cache::new(BLOCK_CACHE_SIZE)),
+            state_root_cache: Arc::new(Cache::new(BLOCK_CACHE_SIZE)),
+            block_head_cache: Arc::new(Cache::new(BLOCK_CACHE_SIZE)),
+            interface_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            receipt_cache: Arc::new(Cache::new(RECEIPT_CACHE_SIZE)),
+            erc20_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            queue_depth_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            pending_nonce_cache: Arc::new(
+                Cache::builder()
+                    .max_capacity(CALL_CACHE_SIZE)
+                    .time_to_live(PENDING_NONCE_TTL)
+                    .build(),
+            ),
+            nonce_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            nonce_counters: Default::default(),
+            storage_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            proof_request_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            proof_fulfilled_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            block_fulfillment_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            deadline_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            input_hash_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            active_deadlines: Default::default(),
+            proof_price_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            proof_retry_tx: None,
+            #[cfg(feature = "etherscan")]
+            abi_cache: Arc::new(Cache::new(CALL_CACHE_SIZE)),
+            #[cfg(feature = "debug-rpc")]
+            block_trace_cache: Arc::new(Cache::new(BLOCK_CACHE_SIZE)),
+        })
+    }
+
+    /// Replaces the default [ChainMonitorConfig].
+    pub fn with_config(self, config: ChainMonitorConfig) -> Self {
+        let poll_errors =
+            ErrorAggregator::new(config.error_alert_window, config.error_alert_threshold);
+        Self { config, poll_errors: Arc::new(std::sync::Mutex::new(poll_errors)), ..self }
+    }
+
+    /// Sets the queue that [Self::retry_pending_proofs] schedules proof re-submissions on.
+    pub fn with_proof_retry_queue(self, proof_retry_tx: mpsc::Sender<ProofRetry>) -> Self {
+        Self { proof_retry_tx: Some(proof_retry_tx), ..self }
+    }
+
+    /// Returns a future that resolves once the service has been cancelled, for use in
+    /// `tokio::select!` loops that should stop with the broker.
+    pub fn listen_for_shutdown(&self) -> impl Future<Output = ()> + Send + 'static {
+        let shutdown = self.shutdown.clone();
+        async move { shutdown.cancelled().await }
+    }
+
+    /// Returns whether the node was fully synced at the last sync status observed by
+    /// [Self::eth_subscribe_syncing].
+    pub fn is_healthy(&self) -> bool {
+        !*self.syncing.borrow()
+    }
+
+    /// Returns whether the service has been cancelled.
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutdown.is_cancelled()
+    }
+
+    /// Returns the family of the monitored chain.
+    pub fn chain_type(&self) -> ChainType {
+        ChainType::from(self.chain_id)
+    }
+
+    /// Returns the transaction types supported by the chain, based on the hardforks activated at
+    /// the latest polled block.
+    ///
+    /// Only legacy transactions are reported until the first block has been polled.
+    pub fn tx_type_support(&self) -> TxTypeSupport {
+        match self.latest_block.borrow().as_deref() {
+            Some(block) => TxTypeSupport::from_header(&block.header),
+            None => TxTypeSupport { legacy: true, ..Default::default() },
+        }
+    }
+
+    /// Returns a snapshot of the cached chain state, without triggering an update.
+    pub fn status(&self) -> ChainMonitorStatus {
+        ChainMonitorStatus {
+            chain_id: self.chain_id,
+            head: *self.head_update.borrow(),
+            gas_price: *self.gas_price.borrow(),
+            queue_depth: *self.queue_depth.borrow(),
+            net_listening: *self.net_listening.borrow(),
+            fulfillment_rate: *self.fulfillment_rate.borrow(),
+        }
+    }
+
+    /// Checks that the provider is usable: that it serves the chain the service was created for,
+    /// and whether its node is listening for peers.
+    ///
+    /// A node that is not listening only logs a warning, as archive nodes commonly disable peer
+    /// connections.
+    pub async fn validate_provider(&self) -> Result<()> {
+        let chain_id = self
+            .provider
+            .get_chain_id()
+            .await
+            .context("failed to get chain ID")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        if chain_id != self.chain_id {
+            bail!("provider chain ID changed from {} to {chain_id}", self.chain_id);
+        }
+
+        if !self.net_listening().await? {
+            tracing::warn!("Node of chain {chain_id} is not listening for peer connections");
+        }
+        Ok(())
+    }
+
+    /// Returns whether the node is listening for peer connections, via `net_listening`.
+    pub async fn net_listening(&self) -> Result<bool> {
+        let listening: bool = self
+            .provider
+            .client()
+            .request_noparams("net_listening")
+            .await
+            .context("failed to query net_listening")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        self.net_listening.send_replace(Some(listening));
+        Ok(listening)
+    }
+
+    /// Measures the round-trip time of an `eth_blockNumber` request to the provider.
+    ///
+    /// Called by the health check of an [RpcPool] to rank its endpoints.
+    pub async fn measure_rpc_latency(&self) -> Result<Duration> {
+        let start = Instant::now();
+        self.provider
+            .get_block_number()
+            .await
+            .context("failed to get block number")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let latency = start.elapsed();
+        self.rpc_latency.send_replace(Some(latency));
+        Ok(latency)
+    }
+
+    /// Benchmarks the given `(url, provider)` pairs against the primary provider, returning the
+    /// results sorted by latency.
+    ///
+    /// Providers do not expose their endpoint, so each one is labelled with the given URL.
+    pub async fn compare_providers(
+        &self,
+        providers: Vec<(String, Arc<P>)>,
+    ) -> Result<Vec<ProviderComparison>> {
+        let primary_block = self
+            .provider
+            .get_block_number()
+            .await
+            .context("failed to get block number of primary provider")
+            .map_err(ChainMonitorErr::RpcErr)?;
+
+        let mut comparisons =
+            try_join_all(providers.into_iter().map(|(url, provider)| async move {
+                let start = Instant::now();
+                let (chain_id, block_number) =
+                    tokio::join!(provider.get_chain_id(), provider.get_block_number());
+                let latency_ms = start.elapsed().as_millis() as u64;
+                let chain_id = chain_id
+                    .with_context(|| format!("failed to get chain ID from {url}"))
+                    .map_err(ChainMonitorErr::RpcErr)?;
+                let block_number = block_number
+                    .with_context(|| format!("failed to get block number from {url}"))
+                    .map_err(ChainMonitorErr::RpcErr)?;
+                anyhow::Ok(ProviderComparison {
+                    agrees_with_primary: chain_id == self.chain_id
+                        && block_number.abs_diff(primary_block) <= PROVIDER_AGREEMENT_BLOCKS,
+                    url,
+                    latency_ms,
+                    chain_id,
+                    block_number,
+                })
+            }))
+            .await?;
+        comparisons.sort_by_key(|comparison| comparison.latency_ms);
+        Ok(comparisons)
+    }
+
+    /// Returns the latency, in milliseconds, of the last [Self::measure_rpc_latency] probe.
+    pub fn current_rpc_latency_ms(&self) -> Option<u64> {
+        self.rpc_latency.borrow().map(|latency| latency.as_millis() as u64)
+    }
+
+    /// Returns the latest block number, triggering an update if enough time has passed
+    pub async fn current_block_number(&self) -> Result<u64> {
+        self.current_chain_head().await.map(|head| head.block_number)
+    }
+
+    pub(crate) async fn current_chain_head(&self) -> Result<ChainHead> {
+        if Instant::now() > *self.next_update.read().await {
+            let mut rx = self.head_update.subscribe();
+            self.update_notifier.notify_one();
+            rx.changed().await.context("failed to query head update from chain monitor")?;
+            let chain_head = *rx.borrow();
+            Ok(chain_head)
+        } else {
+            Ok(*self.head_update.borrow())
+        }
+    }
+
+    /// Returns the transactions of the latest block, as hashes (`Left`) or, if `full` is set, as
+    /// full transactions (`Right`).
+    ///
+    /// Served from the block fetched when polling the chain head. Full transactions are fetched
+    /// on demand unless [ChainMonitorConfig::cache_full_block] is set.
+    pub async fn current_block_transactions(
+        &self,
+        full: bool,
+    ) -> Result<Vec<Either<B256, Transaction>>> {
+        let head = self.current_chain_head().await?;
+        let cached =
+            self.latest_block.borrow().clone().filter(|block| block.header.hash == head.block_hash);
+        let block = match cached {
+            Some(block) if !full || block.transactions.is_full() => block,
+            _ => {
+                let hash = head.block_hash;
+                let mut request = self.provider.get_block_by_hash(hash);
+                if full {
+                    request = request.full();
+                }
+                let block = request
+                    .await
+                    .with_context(|| format!("failed to fetch block {hash}"))
+                    .map_err(ChainMonitorErr::RpcErr)?
+                    .with_context(|| format!("block {hash} not found"))?;
+                Arc::new(block)
+            }
+        };
+
+        Ok(match (&block.transactions, full) {
+            (BlockTransactions::Full(txs), true) => {
+                txs.iter().cloned().map(Either::Right).collect()
+            }
+            (transactions, _) => transactions.hashes().map(Either::Left).collect(),
+        })
+    }
+
+    /// Returns the first transaction of the given block matching `predicate`.
+    ///
+    /// Uses the block fetched when polling the chain head if it is the requested block and
+    /// [ChainMonitorConfig::cache_full_block] is set, and fetches the full block otherwise.
+    pub async fn find_transaction_in_block<F>(
+        &self,
+        block_number: u64,
+        predicate: F,
+    ) -> Result<Option<Transaction>>
+    where
+        F: Fn(&Transaction) -> bool + Send,
+    {
+        let cached =
+            self.latest_block.borrow().clone().filter(|block| {
+                block.header.number == block_number && block.transactions.is_full()
+            });
+        let block = match cached {
+            Some(block) => block,
+            None => Arc::new(
+                self.provider
+                    .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                    .full()
+                    .await
+                    .with_context(|| format!("failed to fetch block {block_number}"))
+                    .map_err(ChainMonitorErr::RpcErr)?
+                    .with_context(|| format!("block {block_number} not found"))?,
+            ),
+        };
+        Ok(block.transactions.txns().find(|tx| predicate(tx)).cloned())
+    }
+
+    /// Recommends a priority fee from the tips of transactions confirmed in the last `window`
+    /// blocks, for clients that do not support `eth_maxPriorityFeePerGas`.
+    ///
+    /// Tips more than three times the median are discarded as outliers, and the 75th percentile
+    /// of the remainder is returned. Falls back to `eth_maxPriorityFeePerGas` if the window holds
+    /// no EIP-1559 transactions.
+    pub async fn max_priority_fee_from_recent_blocks(&self, window: u64) -> Result<u128> {
+        let mut tips = self.recent_priority_fees(window).await?;
+        if tips.is_empty() {
+            return self.node_priority_fee().await;
+        }
+
+        let median = percentile(&tips, 50);
+        tips.retain(|tip| *tip <= median.saturating_mul(3));
+        Ok(percentile(&tips, 75))
+    }
+
+    /// Returns the sorted max priority fees of the EIP-1559 transactions confirmed in the last
+    /// `window` blocks.
+    async fn recent_priority_fees(&self, window: u64) -> Result<Vec<u128>> {
+        let head = self.current_block_number().await?;
+        let start = (head + 1).saturating_sub(window);
+        let blocks = try_join_all((start..=head).map(|number| async move {
+            self.provider
+                .get_block_by_number(BlockNumberOrTag::Number(number))
+                .full()
+                .await
+                .with_context(|| format!("failed to fetch block {number}"))
+                .map_err(ChainMonitorErr::RpcErr)?
+                .with_context(|| format!("block {number} not found"))
+        }))
+        .await?;
+
+        let mut tips: Vec<u128> = blocks
+            .iter()
+            .flat_map(|block| block.transactions.txns())
+            .filter_map(|tx| tx.max_priority_fee_per_gas())
+            .collect();
+        tips.sort_unstable();
+        Ok(tips)
+    }
+
+    async fn node_priority_fee(&self) -> Result<u128> {
+        self.provider
+            .get_max_priority_fee_per_gas()
+            .await
+            .context("failed to get max priority fee per gas")
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+
+    /// Classifies the trend of the base fee over the last `window` blocks by the least-squares
+    /// slope of their base fees.
+    ///
+    /// Fails if fewer than 3 of the blocks have a base fee.
+    pub async fn historical_base_fee_trend(&self, window: u64) -> Result<BaseFeeTrend> {
+        let head = self.current_block_number().await?;
+        let start = (head + 1).saturating_sub(window);
+        let heads = try_join_all((start..=head).map(|number| self.block_head(number))).await?;
+        let base_fees: Vec<f64> = heads
+            .iter()
+            .filter_map(|head| head.base_fee_per_gas)
+            .map(|fee| fee as f64 / 1e9)
+            .collect();
+        if base_fees.len() < 3 {
+            bail!("need at least 3 base fees to compute a trend, got {}", base_fees.len());
+        }
+        Ok(BaseFeeTrend::from_slope(least_squares_slope(&base_fees)))
+    }
+
+    /// Returns the latest `safe` block.
+    ///
+    /// On chains that do not support the `safe` tag, falls back to the block the configured
+    /// finalization depth behind the current head.
+    pub async fn safe_head(&self) -> Result<ChainHead> {
+        self.tagged_head(BlockNumberOrTag::Safe).await
+    }
+
+    /// Returns the number of the latest `safe` block. See [Self::safe_head].
+    pub async fn safe_block_number(&self) -> Result<u64> {
+        self.safe_head().await.map(|head| head.block_number)
+    }
+
+    /// Returns the latest `finalized` block.
+    ///
+    /// On chains that do not support the `finalized` tag, falls back to the block the configured
+    /// finalization depth behind the current head.
+    pub async fn finalized_head(&self) -> Result<ChainHead> {
+        self.tagged_head(BlockNumberOrTag::Finalized).await
+    }
+
+    /// Returns the number of the latest `finalized` block. See [Self::finalized_head].
+    pub async fn finalized_block_number(&self) -> Result<u64> {
+        self.finalized_head().await.map(|head| head.block_number)
+    }
+
+    async fn tagged_head(&self, tag: BlockNumberOrTag) -> Result<ChainHead> {
+        match self.provider.get_block_by_number(tag).await {
+            Ok(Some(block)) => return Ok(ChainHead::from_block(&block)),
+            Ok(None) => {}
+            Err(err) if err.as_error_resp().is_some() => {
+                tracing::debug!("Block tag {tag} not supported, falling back to head depth: {err}")
+            }
+            Err(err) => {
+                return Err(ChainMonitorErr::RpcErr(
+                    anyhow::Error::new(err).context(format!("failed to fetch {tag} block")),
+                )
+                .into())
+            }
+        }
+
+        let head = self.current_block_number().await?;
+        let block = self.fetch_block(head.saturating_sub(self.config.finalization_depth)).await?;
+        Ok(ChainHead::from_block(&block))
+    }
+
+    /// Returns the gas price (as reported by `eth_gasPrice`) at the latest block.
+    /// This triggers an update if enough time has passed.
+    pub async fn current_gas_price(&self) -> Result<u128> {
+        if Instant::now() > *self.next_update.read().await {
+            let mut rx = self.gas_price.subscribe();
+            self.update_notifier.notify_one();
+            rx.changed().await.context("failed to query gas price from chain monitor")?;
+            let gas_price = *rx.borrow();
+            Ok(gas_price)
+        } else {
+            Ok(*self.gas_price.borrow())
+        }
+    }
+
+    /// Returns the current gas price, fetched from the node, provided it is at most
+    /// `max_slippage_bps` basis points above the `estimated` gas price.
+    ///
+    /// Fails with [ChainMonitorErr::GasPriceTooHigh] otherwise, so that submissions are not sent
+    /// into a gas price spike.
+    pub async fn slippage_protected_gas_price(
+        &self,
+        estimated: u128,
+        max_slippage_bps: u16,
+    ) -> Result<u128> {
+        let current = self
+            .provider
+            .get_gas_price()
+            .await
+            .context("failed to get gas price")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let max_allowed = estimated.saturating_mul(10_000 + max_slippage_bps as u128) / 10_000;
+        if current > max_allowed {
+            return Err(ChainMonitorErr::GasPriceTooHigh { current, max_allowed }.into());
+        }
+        Ok(current)
+    }
+
+    /// Recommends EIP-1559 fees for a transaction of the given `urgency`.
+    ///
+    /// The priority fee is taken from the tips of recent blocks, see
+    /// [Self::max_priority_fee_from_recent_blocks], and the max fee adds it to the base fee the
+    /// urgency can absorb. On chains without a base fee, both fees are the current gas price.
+    pub async fn recommended_gas_price(&self, urgency: Urgency) -> Result<Eip1559Estimation> {
+        let head = self.current_chain_head().await?;
+        let Some(base_fee) = head.base_fee_per_gas else {
+            let gas_price = self.current_gas_price().await?;
+            return Ok(Eip1559Estimation {
+                max_fee_per_gas: gas_price,
+                max_priority_fee_per_gas: gas_price,
+            });
+        };
+
+        let priority_fee =
+            self.max_priority_fee_from_recent_blocks(PRIORITY_FEE_SAMPLE_SIZE).await?;
+        Ok(Eip1559Estimation {
+            max_fee_per_gas: urgency.max_base_fee(base_fee).saturating_add(priority_fee),
+            max_priority_fee_per_gas: priority_fee,
+        })
+    }
+
+    /// Returns the max fee per gas, in wei, at which a proof submission using `gas_estimate` gas
+    /// stays within `eth_budget` wei, capped at the configured absolute max fee per gas.
+    pub async fn max_proof_price_for_budget(
+        &self,
+        eth_budget: U256,
+        gas_estimate: u64,
+    ) -> Result<u128> {
+        if gas_estimate == 0 {
+            bail!("gas estimate must be non-zero");
+        }
+        let max_fee = u128::try_from(eth_budget / U256::from(gas_estimate)).unwrap_or(u128::MAX);
+        Ok(self.config.absolute_max_fee_per_gas.map_or(max_fee, |cap| max_fee.min(cap)))
+    }
+
+    /// Returns a snapshot of the gas market at the current head, for debugging the cost of
+    /// submissions.
+    ///
+    /// The expected confirmation time assumes the base fee falls at its maximum rate of 12.5% per
+    /// block until a transaction paying the current gas price and the median priority fee fits.
+    pub async fn gas_tracker_report(&self) -> Result<GasTrackerReport> {
+        let head = self.current_block_number().await?;
+        let (block, gas_price, ema_heads, day_average, tips, node_tip, blob_base_fee) = tokio::join!(
+            self.fetch_block(head),
+            self.current_gas_price(),
+            try_join_all(
+                ((head + 1).saturating_sub(GAS_PRICE_EMA_SAMPLE_SIZE)..=head)
+                    .map(|number| self.block_head(number))
+            ),
+            self.gas_price_24h_avg(head),
+            self.recent_priority_fees(PRIORITY_FEE_SAMPLE_SIZE),
+            self.node_priority_fee(),
+            self.provider.get_blob_base_fee(),
+        );
+        let block = block?;
+        let gas_price = gas_price?;
+        let tips = tips?;
+
+        let base_fees: Vec<f64> = ema_heads?
+            .iter()
+            .filter_map(|head| head.base_fee_per_gas)
+            .map(|fee| fee as f64)
+            .collect();
+        let gas_price_ema = if base_fees.is_empty() {
+            gas_price
+        } else {
+            exponential_moving_average(&base_fees) as u128
+        };
+
+        let (priority_fee_p50, priority_fee_p75) = if tips.is_empty() {
+            let tip = node_tip?;
+            (tip, tip)
+        } else {
+            (percentile(&tips, 50), percentile(&tips, 75))
+        };
+
+        let header = &block.header.inner;
+        let chain_util_pct = if header.gas_limit == 0 {
+            0.0
+        } else {
+            header.gas_used as f64 * 100.0 / header.gas_limit as f64
+        };
+
+        let estimated_confirmation_blocks = confirmation_blocks(
+            header.next_block_base_fee(BaseFeeParams::ethereum()),
+            gas_price,
+            priority_fee_p50,
+        );
+
+        Ok(GasTrackerReport {
+            current_base_fee: header.base_fee_per_gas.map(u128::from),
+            current_gas_price: gas_price,
+            gas_price_ema,
+            gas_price_24h_avg: day_average,
+            priority_fee_p50,
+            priority_fee_p75,
+            blob_base_fee: header.blob_gas_used.and(blob_base_fee.ok()),
+            chain_util_pct,
+            estimated_confirmation_blocks,
+        })
+    }
+
+    /// Returns the fee market signals at the current head, fetched concurrently.
+    ///
+    /// The base fee trend is taken over the last 10 blocks, and reported as
+    /// [BaseFeeTrend::Stable] when they do not have enough base fees to compute it.
+    pub async fn fee_market_summary(&self) -> Result<FeeMarketSummary> {
+        let head = self.current_block_number().await?;
+        let (block, gas_price, tips, node_tip, blob_base_fee, trend) = tokio::join!(
+            self.fetch_block(head),
+            self.current_gas_price(),
+            self.recent_priority_fees(PRIORITY_FEE_SAMPLE_SIZE),
+            self.node_priority_fee(),
+            self.provider.get_blob_base_fee(),
+            self.historical_base_fee_trend(BASE_FEE_TREND_SAMPLE_SIZE),
+        );
+        let block = block?;
+        let tips = tips?;
+
+        let (priority_fee_p50, priority_fee_p75, priority_fee_p95) = if tips.is_empty() {
+            let tip = node_tip?;
+            (tip, tip, tip)
+        } else {
+            (percentile(&tips, 50), percentile(&tips, 75), percentile(&tips, 95))
+        };
+
+        let header = &block.header.inner;
+        let gas_utilization = if header.gas_limit == 0 {
+            0.0
+        } else {
+            header.gas_used as f64 / header.gas_limit as f64
+        };
+        let base_fee_trend = trend
+            .inspect_err(|err| tracing::debug!("Failed to compute base fee trend: {err:?}"))
+            .unwrap_or(BaseFeeTrend::Stable);
+
+        Ok(FeeMarketSummary {
+            legacy_gas_price: gas_price?,
+            base_fee: header.base_fee_per_gas.map(u128::from),
+            priority_fee_p50,
+            priority_fee_p75,
+            priority_fee_p95,
+            blob_base_fee: header.blob_gas_used.and(blob_base_fee.ok()),
+            gas_utilization,
+            base_fee_trend,
+            next_base_fee_estimate: header
+                .next_block_base_fee(BaseFeeParams::ethereum())
+                .map(u128::from),
+        })
+    }
+
+    /// Estimates the time from now until a proof of request `request_id` of the proof-request
+    /// contract at `contract` is generated, submitted at the current gas price, and final.
+    ///
+    /// Sums the configured proof generation overhead, the [Self::estimate_confirmation_time] of
+    /// the submission at the current gas price and the [Self::estimate_time_to_finality]. Requests
+    /// already fulfilled have no latency.
+    pub async fn estimate_proof_latency(
+        &self,
+        contract: Address,
+        request_id: U256,
+    ) -> Result<Duration> {
+        if self.get_proof_fulfilled_event(contract, request_id).await?.is_some() {
+            return Ok(Duration::ZERO);
+        }
+
+        let gas_price = self.current_gas_price().await?;
+        let (confirmation, finality) = tokio::try_join!(
+            self.estimate_confirmation_time(gas_price),
+            self.estimate_time_to_finality()
+        )?;
+        Ok(self
+            .config
+            .proof_generation_overhead
+            .saturating_add(confirmation)
+            .saturating_add(finality))
+    }
+
+    /// Estimates the time until a transaction paying `gas_price` wei per gas is included, at the
+    /// average block time.
+    ///
+    /// Assumes the transaction tips the median recent priority fee and the base fee falls at its
+    /// maximum rate of 12.5% per block until the transaction fits.
+    pub async fn estimate_confirmation_time(&self, gas_price: u128) -> Result<Duration> {
+        let head = self.current_block_number().await?;
+        let (block, tips, block_time) = tokio::try_join!(
+            self.fetch_block(head),
+            self.recent_priority_fees(PRIORITY_FEE_SAMPLE_SIZE),
+            self.average_block_time(),
+        )?;
+        let priority_fee =
+            if tips.is_empty() { self.node_priority_fee().await? } else { percentile(&tips, 50) };
+
+        let blocks = confirmation_blocks(
+            block.header.inner.next_block_base_fee(BaseFeeParams::ethereum()),
+            gas_price,
+            priority_fee,
+        );
+        Ok(blocks_duration(block_time, blocks))
+    }
+
+    /// Estimates the time for an included transaction to be buried under the configured
+    /// finalization depth, at the average block time.
+    pub async fn estimate_time_to_finality(&self) -> Result<Duration> {
+        let block_time = self.average_block_time().await?;
+        Ok(blocks_duration(block_time, self.config.finalization_depth))
+    }
+
+    /// Returns the mean base fee of blocks sampled hourly over the 24 hours before block `head`,
+    /// or `None` if the chain has no base fee or the blocks cannot be fetched.
+    async fn gas_price_24h_avg(&self, head: u64) -> Option<u128> {
+        let block_time = self.average_block_time().await.ok()?.as_secs_f64();
+        let blocks_per_hour = ((3600.0 / block_time.max(f64::EPSILON)) as u64).max(1);
+        let samples = (0..GAS_PRICE_DAY_SAMPLES)
+            .filter_map(|hour| head.checked_sub(hour * blocks_per_hour))
+            .map(|number| self.block_head(number));
+        let base_fees: Vec<u128> = try_join_all(samples)
+            .await
+            .inspect_err(|err| tracing::debug!("Failed to sample 24 hour base fees: {err:?}"))
+            .ok()?
+            .iter()
+            .filter_map(|head| head.base_fee_per_gas)
+            .collect();
+        if base_fees.is_empty() {
+            return None;
+        }
+        Some(base_fees.iter().sum::<u128>() / base_fees.len() as u128)
+    }
+
+    /// Returns the cost, in USD, of a simple transfer (21,000 gas) at the current gas price.
+    pub async fn gas_price_in_usd(&self, usd_per_eth: f64) -> Result<f64> {
+        let gas_price = self.current_gas_price().await?;
+        let cost_eth = gas_price as f64 * SIMPLE_TRANSFER_GAS as f64 / 1e18;
+        Ok(cost_eth * usd_per_eth)
+    }
+
+    /// Returns the ETH/USD exchange rate reported by the configured Chainlink price feed, or
+    /// `None` if no feed is configured.
+    pub async fn eth_usd_price(&self) -> Result<Option<f64>> {
+        let Some(feed_address) = self.config.eth_usd_price_feed else {
+            return Ok(None);
+        };
+        let feed = IAggregatorV3::new(feed_address, self.provider.clone());
+
+        let decimals_call = feed.decimals();
+        let round_call = feed.latestRoundData();
+        let (decimals, round) = tokio::join!(decimals_call.call(), round_call.call());
+        let decimals = decimals
+            .context("failed to query price feed decimals")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let round = round
+            .context("failed to query price feed round data")
+            .map_err(ChainMonitorErr::RpcErr)?;
+
+        if round.answer.is_negative() {
+            bail!("price feed {feed_address} reported a negative price: {}", round.answer);
+        }
+        let answer = u128::try_from(round.answer.into_raw())
+            .with_context(|| format!("price feed {feed_address} answer out of range"))?;
+        Ok(Some(answer as f64 / 10f64.powi(decimals.into())))
+    }
+
+    /// Returns the ratio of the average gas price over the last `window_blocks` blocks, in wei, to
+    /// the ETH/USD price of the configured price feed.
+    ///
+    /// This is an informational signal for submission strategies comparing fee levels against the
+    /// ETH price, not a financial recommendation. Fails if no price feed is configured.
+    #[cfg(feature = "oracle")]
+    pub async fn gas_token_price_ratio(&self, window_blocks: u64) -> Result<f64> {
+        let (gas_price, eth_price) =
+            tokio::try_join!(self.average_gas_price(window_blocks), self.eth_usd_price())?;
+        let eth_price = eth_price.context("no ETH/USD price feed configured")?;
+        if eth_price <= 0.0 {
+            bail!("ETH/USD price feed reported a non-positive price: {eth_price}");
+        }
+        Ok(gas_price / eth_price)
+    }
+
+    /// Returns the mean base fee of the last `window` blocks, in wei, falling back to the current
+    /// gas price on chains without a base fee.
+    #[cfg(feature = "oracle")]
+    async fn average_gas_price(&self, window: u64) -> Result<f64> {
+        let head = self.current_block_number().await?;
+        let start = (head + 1).saturating_sub(window);
+        let blocks = try_join_all((start..=head).map(|number| self.fetch_block(number))).await?;
+        let base_fees: Vec<u64> =
+            blocks.iter().filter_map(|block| block.header.base_fee_per_gas).collect();
+        if base_fees.is_empty() {
+            return Ok(self.current_gas_price().await? as f64);
+        }
+        Ok(base_fees.iter().map(|fee| *fee as f64).sum::<f64>() / base_fees.len() as f64)
+    }
+
+    /// Estimates the gas used by `tx` via `eth_estimateGas`, using its intrinsic gas as a lower
+    /// bound.
+    pub async fn gas_estimate(&self, tx: TransactionRequest) -> Result<u64> {
+        let intrinsic_gas = intrinsic_gas(&tx);
+        let estimate = self
+            .provider
+            .estimate_gas(tx)
+            .await
+            .context("failed to estimate gas")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        Ok(estimate.max(intrinsic_gas))
+    }
+
+    /// Estimates the storage refund, in gas, received by the mined transaction `tx`, from the
+    /// storage slots pre-warmed by its access list.
+    ///
+    /// Multiply by the receipt's `effective_gas_price` for the refund in wei.
+    pub async fn gas_refund_estimate(&self, tx: &Transaction) -> Result<u64> {
+        let tx_hash = tx.tx_hash();
+        let receipt = self
+            .fetch_receipt(tx_hash)
+            .await?
+            .with_context(|| format!("{tx_hash} is not mined"))?;
+        let slots = tx
+            .access_list()
+            .map_or(0, |list| list.iter().map(|item| item.storage_keys.len() as u64).sum());
+        let refund = refund_estimate(receipt.gas_used, slots);
+        tracing::debug!(
+            "Estimated refund of {refund} gas at {} wei/gas for {tx_hash}",
+            receipt.effective_gas_price
+        );
+        Ok(refund)
+    }
+
+    /// Estimates the gas used to deploy a contract with the given creation `bytecode` and
+    /// ABI-encoded `constructor_args`, increased by the configured gas estimate buffer.
+    pub async fn estimate_gas_for_deployment(
+        &self,
+        bytecode: Bytes,
+        constructor_args: Bytes,
+    ) -> Result<u64> {
+        let input = [bytecode, constructor_args].concat();
+        let estimate =
+            self.gas_estimate(TransactionRequest::default().with_deploy_code(input)).await?;
+        Ok(estimate + estimate * self.config.gas_estimate_buffer / 100)
+    }
+
+    /// Returns the withdrawals root of the latest block, or `None` on pre-Shanghai chains.
+    pub async fn current_withdrawal_credentials_root(&self) -> Result<Option<B256>> {
+        self.current_chain_head().await.map(|head| head.withdrawals_root)
+    }
+
+    /// Returns the difficulty of the last polled block, or `None` if it is a PoS block.
+    pub fn current_difficulty(&self) -> Option<U256> {
+        self.head_update.borrow().difficulty
+    }
+
+    /// Returns the mix hash of the last polled block, or `None` if it is a PoS block.
+    pub fn current_mix_hash(&self) -> Option<B256> {
+        self.head_update.borrow().mix_hash
+    }
+
+    /// Returns whether the last polled block processed any beacon chain withdrawals.
+    pub fn has_withdrawals(&self) -> bool {
+        self.head_update.borrow().has_withdrawals
+    }
+
+    /// Builds a [Filter] from a human-readable event signature such as
+    /// `Transfer(address indexed from, address indexed to, uint256 value)`, with the cached chain
+    /// head as the upper bound of the block range.
+    pub fn event_abi_to_filter(
+        &self,
+        event_abi: &str,
+        address: Option<Address>,
+        from_block: u64,
+    ) -> Result<Filter> {
+        let event =
+            Event::parse(event_abi).with_context(|| format!("invalid event ABI: {event_abi}"))?;
+        if event.anonymous {
+            bail!("anonymous event {} has no signature topic", event.name);
+        }
+
+        let filter = Filter::new()
+            .event_signature(event.selector())
+            .from_block(from_block)
+            .to_block(self.head_update.borrow().block_number);
+        Ok(match address {
+            Some(address) => filter.address(address),
+            None => filter,
+        })
+    }
+
+    /// Fetches the logs from `from_block` up to the current head matching the given indexed
+    /// topics, starting with `topic0`. `None` entries match any topic.
+    pub async fn logs_topic_filter(
+        &self,
+        address: Option<Address>,
+        topics: Vec<Option<B256>>,
+        from_block: u64,
+    ) -> Result<Vec<Log>> {
+        let filter = topic_filter(address, topics)?;
+        let head = self.current_block_number().await?;
+        self.provider
+            .get_logs(&filter.from_block(from_block).to_block(head))
+            .await
+            .context("failed to fetch logs")
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+
+    /// Fetches the logs matching `filter` from `from_block` to `to_block`, in pages of 1000
+    /// blocks.
+    async fn get_logs_paged(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<Log>> {
+        let mut logs = Vec::new();
+        for (start, end) in log_pages(from_block, to_block) {
+            logs.extend(self.get_logs_between(filter, start, end).await?);
+        }
+        Ok(logs)
+    }
+
+    /// Fetches the logs matching `filter` from `from_block` to `to_block` in a single request.
+    async fn get_logs_between(
+        &self,
+        filter: &Filter,
+        from_block: u64,
+        to_block: u64,
+    ) -> Result<Vec<Log>> {
+        let filter = filter.clone().from_block(from_block).to_block(to_block);
+        self.provider
+            .get_logs(&filter)
+            .await
+            .with_context(|| format!("failed to fetch logs from block {from_block} to {to_block}"))
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+
+    /// Fetches the logs matching `filter` after the last block processed by `cursor`, up to the
+    /// current head.
+    ///
+    /// Logs are fetched in pages of 1000 blocks. After each page, the cursor is advanced past it
+    /// and persisted to the configured cursor path, so that a restarted broker does not process
+    /// the same logs again. If a page fails after earlier pages were fetched, the logs of the
+    /// earlier pages are returned and the next call resumes from the failed page.
+    pub async fn get_logs_since_last_run(
+        &self,
+        cursor: &mut PersistentCursor,
+        filter: Filter,
+    ) -> Result<Vec<Log>> {
+        let head = self.current_block_number().await?;
+        let from_block = cursor.last_processed_block + 1;
+
+        let mut logs = Vec::new();
+        for (index, (start, end)) in log_pages(from_block, head).enumerate() {
+            match self.get_logs_between(&filter, start, end).await {
+                Ok(page) => logs.extend(page),
+                Err(err) if index > 0 => {
+                    tracing::warn!(
+                        "Failed to fetch logs from block {start}, resuming from it on the next \
+                         run: {err:?}"
+                    );
+                    break;
+                }
+                Err(err) => return Err(err),
+            }
+            cursor.last_processed_block = end;
+            if let Some(path) = &self.config.cursor_path {
+                cursor.save(path).await?;
+            }
+        }
+        Ok(logs)
+    }
+
+    /// Returns whether the contract at `address` reports itself as paused, by calling the
+    /// zero-argument view function identified by `pause_selector` (e.g. `paused()`) at the
+    /// current block.
+    ///
+    /// Contracts that revert or do not return a boolean are reported as not paused.
+    pub async fn is_contract_paused(
+        &self,
+        address: Address,
+        pause_selector: [u8; 4],
+    ) -> Result<bool> {
+        let block_number = self.current_block_number().await?;
+        if let Some(paused) = self.paused_cache.get(&(address, block_number)).await {
+            return Ok(paused);
+        }
+
+        let paused = self
+            .try_call_at(address, Bytes::copy_from_slice(&pause_selector), block_number)
+            .await?
+            .and_then(|output| sol_data::Bool::abi_decode(&output).ok())
+            .unwrap_or(false);
+        self.paused_cache.insert((address, block_number), paused).await;
+        Ok(paused)
+    }
+
+    /// Fetches an `eth_getProof` storage proof for `slot` of the contract at `address`, as of
+    /// `block`.
+    pub async fn storage_proof(
+        &self,
+        address: Address,
+        slot: U256,
+        block: u64,
+    ) -> Result<StorageProof> {
+        if let Some(proof) = self.storage_proof_cache.get(&(address, slot, block)).await {
+            return Ok(proof);
+        }
+
+        let response = self
+            .provider
+            .get_proof(address, vec![B256::from(slot.to_be_bytes::<32>())])
+            .block_id(BlockId::number(block))
+            .await
+            .with_context(|| format!("failed to get storage proof for {address} at block {block}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let storage_proof = response
+            .storage_proof
+            .into_iter()
+            .next()
+            .with_context(|| format!("empty storage proof for {address} slot {slot}"))?;
+
+        let proof =
+            StorageProof { key: slot, value: storage_proof.value, proof: storage_proof.proof };
+        self.storage_proof_cache.insert((address, slot, block), proof.clone()).await;
+        Ok(proof)
+    }
+
+    /// Fetches the header of the block with the given `hash`.
+    ///
+    /// Returns an [ChainMonitorErr::UnexpectedErr] if the node does not know the block, e.g.
+    /// because it was reorged out of the canonical chain.
+    pub async fn get_block_by_hash(&self, hash: B256) -> Result<ChainHead> {
+        if let Some(head) = self.block_hash_cache.get(&hash).await {
+            return Ok(head);
+        }
+
+        let block = self
+            .provider
+            .get_block_by_hash(hash)
+            .await
+            .with_context(|| format!("failed to fetch block {hash}"))
+            .map_err(ChainMonitorErr::RpcErr)?
+            .ok_or_else(|| {
+                ChainMonitorErr::UnexpectedErr(anyhow::anyhow!("block {hash} not found"))
+            })?;
+
+        let head = ChainHead::from_block(&block);
+        self.block_hash_cache.insert(hash, head).await;
+        Ok(head)
+    }
+
+    /// Returns the state root of the given block.
+    ///
+    /// Results are cached once the block is buried under the finalization depth.
+    pub async fn account_state_root(&self, block_number: u64) -> Result<B256> {
+        if let Some(state_root) = self.state_root_cache.get(&block_number).await {
+            return Ok(state_root);
+        }
+
+        let state_root = self.fetch_block(block_number).await?.header.state_root;
+        let head = self.current_block_number().await?;
+        if block_number + self.config.finalization_depth <= head {
+            self.state_root_cache.insert(block_number, state_root).await;
+        }
+        Ok(state_root)
+    }
+
+    /// Returns the state root of the latest block.
+    pub async fn current_state_root(&self) -> Result<B256> {
+        self.current_chain_head().await.map(|head| head.state_root)
+    }
+
+    /// Returns the transactions trie root of the given block.
+    pub async fn transactions_root(&self, block_number: u64) -> Result<B256> {
+        self.block_head(block_number).await.map(|head| head.transactions_root)
+    }
+
+    /// Returns the receipts trie root of the given block.
+    pub async fn receipts_root(&self, block_number: u64) -> Result<B256> {
+        self.block_head(block_number).await.map(|head| head.receipts_root)
+    }
+
+    /// Returns the hash of the parent of the given block.
+    pub async fn block_parent_hash(&self, block_number: u64) -> Result<B256> {
+        self.block_head(block_number).await.map(|head| head.parent_hash)
+    }
+
+    /// Fetches the header summary of the given block.
+    ///
+    /// Summaries are cached per block number once the block is buried under the finalization
+    /// depth, so that a reorg cannot leave a replaced block in the cache.
+    async fn block_head(&self, block_number: u64) -> Result<ChainHead> {
+        if let Some(head) = self.block_head_cache.get(&block_number).await {
+            return Ok(head);
+        }
+
+        let block_head = ChainHead::from_block(&self.fetch_block(block_number).await?);
+        let head = self.current_block_number().await?;
+        if block_number + self.config.finalization_depth <= head {
+            self.block_head_cache.insert(block_number, block_head).await;
+        }
+        Ok(block_head)
+    }
+
+    /// Returns the RLP encoding of the header of the given block.
+    ///
+    /// The encoding is checked against the block hash reported by the node.
+    pub async fn block_header_rlp(&self, block_number: u64) -> Result<Bytes> {
+        if let Some(rlp) = self.header_rlp_cache.get(&block_number).await {
+            return Ok(rlp);
+        }
+
+        let block = self.fetch_block(block_number).await?;
+        let mut rlp = Vec::with_capacity(block.header.inner.length());
+        block.header.inner.encode(&mut rlp);
+        let hash = keccak256(&rlp);
+        if hash != block.header.hash {
+            bail!(
+                "RLP-encoded header of block {block_number} hashes to {hash}, expected {}",
+                block.header.hash
+            );
+        }
+
+        let rlp = Bytes::from(rlp);
+        self.header_rlp_cache.insert(block_number, rlp.clone()).await;
+        Ok(rlp)
+    }
+
+    /// Fetches a block with its full transactions and all of its receipts, issuing both requests
+    /// concurrently.
+    pub async fn get_block_with_receipts(&self, block_number: u64) -> Result<BlockWithReceipts> {
+        if let Some(bundle) = self.block_receipts_cache.get(&block_number).await {
+            return Ok(bundle);
+        }
+
+        let (block, receipts) = tokio::join!(
+            self.provider.get_block_by_number(BlockNumberOrTag::Number(block_number)).full(),
+            self.provider.get_block_receipts(BlockId::number(block_number))
+        );
+        let block = block
+            .with_context(|| format!("failed to fetch block {block_number}"))
+            .map_err(ChainMonitorErr::RpcErr)?
+            .with_context(|| format!("block {block_number} not found"))?;
+        let receipts = receipts
+            .with_context(|| format!("failed to fetch receipts for block {block_number}"))
+            .map_err(ChainMonitorErr::RpcErr)?
+            .with_context(|| format!("receipts for block {block_number} not found"))?;
+
+        let head = ChainHead::from_block(&block);
+        let BlockTransactions::Full(transactions) = block.transactions else {
+            bail!("block {block_number} response is missing full transactions");
+        };
+        if transactions.len() != receipts.len() {
+            bail!(
+                "block {block_number} has {} transactions but {} receipts",
+                transactions.len(),
+                receipts.len()
+            );
+        }
+
+        let bundle = BlockWithReceipts { head, transactions, receipts };
+        self.block_receipts_cache.insert(block_number, bundle.clone()).await;
+        Ok(bundle)
+    }
+
+    /// Builds a Merkle proof of the receipt of `tx_hash` against the receipts root of its block,
+    /// proving the inclusion of its log with the block-level index `log_index`.
+    ///
+    /// The receipts trie of the block is rebuilt from all of its receipts and checked against
+    /// the receipts root of the block header.
+    pub async fn get_log_proof(&self, tx_hash: B256, log_index: u64) -> Result<LogInclusionProof> {
+        let receipt =
+            self.fetch_receipt(tx_hash).await?.with_context(|| format!("{tx_hash} is pending"))?;
+        if !receipt.inner.logs().iter().any(|log| log.log_index == Some(log_index)) {
+            bail!("transaction {tx_hash} did not emit log {log_index}");
+        }
+        let block_number =
+            receipt.block_number.with_context(|| format!("receipt of {tx_hash} has no block"))?;
+        let receipt_index = receipt
+            .transaction_index
+            .with_context(|| format!("receipt of {tx_hash} has no transaction index"))?;
+
+        let bundle = self.get_block_with_receipts(block_number).await?;
+        let target = Nibbles::unpack(alloy::rlp::encode_fixed_size(&receipt_index));
+        let mut leaves: Vec<(Nibbles, Vec<u8>)> = bundle
+            .receipts
+            .iter()
+            .enumerate()
+            .map(|(i, receipt)| {
+                (Nibbles::unpack(alloy::rlp::encode_fixed_size(&i)), encode_receipt(receipt))
+            })
+            .collect();
+        leaves.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut builder =
+            HashBuilder::default().with_proof_retainer(ProofRetainer::new(vec![target]));
+        for (key, value) in leaves {
+            builder.add_leaf(key, &value);
+        }
+        let root = builder.root();
+        if root != bundle.head.receipts_root {
+            bail!(
+                "receipts trie of block {block_number} has root {root}, expected {}",
+                bundle.head.receipts_root
+            );
+        }
+
+        let receipt_proof = builder
+            .take_proof_nodes()
+            .into_nodes_sorted()
+            .into_iter()
+            .map(|(_, node)| node)
+            .collect();
+        Ok(LogInclusionProof { receipt_proof, log_index, receipt_index })
+    }
+
+    /// Computes the PoW mining rewards of a block: the static block reward plus 1/32 of it for
+    /// each included uncle.
+    ///
+    /// Follows the Ethereum mainnet reward schedule (EIP-649, EIP-1234), with other PoW chains
+    /// assumed to be on the latest schedule. PoS blocks, which have zero difficulty, have no
+    /// mining rewards.
+    pub async fn block_rewards(&self, block_number: u64) -> Result<BlockRewards> {
+        let block = self.fetch_block(block_number).await?;
+        if block.header.difficulty.is_zero() {
+            return Ok(BlockRewards::zero());
+        }
+
+        let base_reward = match (self.chain_id, block_number) {
+            (1, ..MAINNET_BYZANTIUM_BLOCK) => FRONTIER_BLOCK_REWARD,
+            (1, ..MAINNET_CONSTANTINOPLE_BLOCK) => BYZANTIUM_BLOCK_REWARD,
+            _ => CONSTANTINOPLE_BLOCK_REWARD,
+        };
+        let uncle_inclusion_rewards = vec![base_reward / U256::from(32); block.uncles.len()];
+        let total = uncle_inclusion_rewards.iter().fold(base_reward, |acc, reward| acc + *reward);
+        Ok(BlockRewards { base_reward, uncle_inclusion_rewards, total })
+    }
+
+    /// Returns the fee recipient (coinbase) of the given block.
+    pub async fn block_coinbase(&self, block_number: u64) -> Result<Address> {
+        self.fetch_block(block_number).await.map(|block| block.header.beneficiary)
+    }
+
+    /// Returns the extra data field of the given block.
+    pub async fn block_extra_data(&self, block_number: u64) -> Result<Bytes> {
+        self.fetch_block(block_number).await.map(|block| block.header.extra_data.clone())
+    }
+
+    /// Returns the extra data of the given block decoded as UTF-8 with null bytes removed, or
+    /// `None` if it is not valid UTF-8.
+    pub async fn parse_block_extra_data(&self, block_number: u64) -> Result<Option<String>> {
+        self.block_extra_data(block_number).await.map(|data| decode_extra_data(&data))
+    }
+
+    /// Returns whether the given block was built by a Flashbots builder, judging by its coinbase
+    /// or the builder marker in its extra data.
+    pub async fn detect_flashbots_builder(&self, block_number: u64) -> Result<bool> {
+        let block = self.fetch_block(block_number).await?;
+        if FLASHBOTS_BUILDER_ADDRESSES.contains(&block.header.beneficiary) {
+            return Ok(true);
+        }
+        let extra_data = &block.header.extra_data;
+        Ok(FLASHBOTS_EXTRA_DATA_MARKERS
+            .iter()
+            .any(|marker| extra_data.windows(marker.len()).any(|window| window == *marker)))
+    }
+
+    /// Returns the coinbase addresses of the last `window` blocks, oldest first.
+    ///
+    /// Also updates the [Self::dominant_builder] to the most frequent coinbase in the window.
+    pub async fn recent_coinbases(&self, window: u64) -> Result<Vec<Address>> {
+        let head = self.current_block_number().await?;
+        let start = (head + 1).saturating_sub(window);
+        let coinbases =
+            try_join_all((start..=head).map(|number| self.block_coinbase(number))).await?;
+
+        let mut counts = std::collections::HashMap::<Address, usize>::new();
+        for coinbase in &coinbases {
+            *counts.entry(*coinbase).or_default() += 1;
+        }
+        let dominant = counts.into_iter().max_by_key(|(_, count)| *count).map(|(addr, _)| addr);
+        self.dominant_builder.send_replace(dominant);
+        Ok(coinbases)
+    }
+
+    /// Returns the most frequent coinbase seen by the last call to [Self::recent_coinbases].
+    pub fn dominant_builder(&self) -> Option<Address> {
+        *self.dominant_builder.borrow()
+    }
+
+    /// Returns the maximum number of blobs per block at the current head.
+    ///
+    /// Returns 0 on chains without blob transactions, i.e. whose head has no `blobGasUsed`
+    /// field. The Prague limit is applied to Ethereum networks after their Prague activation, and
+    /// the Cancun limit to all other blob-carrying chains.
+    pub async fn current_max_blob_count(&self) -> Result<u8> {
+        let head = self.current_block_number().await?;
+        let block = self.fetch_block(head).await?;
+        if block.header.blob_gas_used.is_none() {
+            return Ok(0);
+        }
+
+        let prague_timestamp = match NamedChain::try_from(self.chain_id) {
+            Ok(NamedChain::Mainnet) => Some(MAINNET_PRAGUE_TIMESTAMP),
+            Ok(NamedChain::Sepolia) => Some(SEPOLIA_PRAGUE_TIMESTAMP),
+            Ok(NamedChain::Holesky) => Some(HOLESKY_PRAGUE_TIMESTAMP),
+            _ => None,
+        };
+        Ok(match prague_timestamp {
+            Some(activation) if block.header.timestamp >= activation => PRAGUE_MAX_BLOB_COUNT,
+            _ => CANCUN_MAX_BLOB_COUNT,
+        })
+    }
+
+    /// Returns the blob fee, in wei, of a transaction carrying `blob_count` blobs at the current
+    /// blob base fee.
+    pub async fn estimate_blob_gas(&self, blob_count: u64) -> Result<u128> {
+        let blob_base_fee = self
+            .provider
+            .get_blob_base_fee()
+            .await
+            .context("failed to get blob base fee")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        (blob_count as u128)
+            .checked_mul(DATA_GAS_PER_BLOB as u128)
+            .and_then(|blob_gas| blob_gas.checked_mul(blob_base_fee))
+            .with_context(|| format!("blob fee of {blob_count} blobs overflows"))
+    }
+
+    /// Estimates the total fee, in wei, of a blob transaction carrying `blob_count` blobs and
+    /// using `execution_gas` gas, at the current blob base fee and gas price.
+    pub async fn blob_transaction_total_cost(
+        &self,
+        blob_count: u64,
+        execution_gas: u64,
+    ) -> Result<U256> {
+        let (blob_fee, gas_price) =
+            tokio::try_join!(self.estimate_blob_gas(blob_count), self.current_gas_price())?;
+        Ok(U256::from(blob_fee) + U256::from(execution_gas) * U256::from(gas_price))
+    }
+
+    /// Returns the number of blobs included in each of the last `window` blocks, oldest first.
+    ///
+    /// Blocks without a `blobGasUsed` field are reported as containing no blobs.
+    pub async fn blobs_per_block_history(&self, window: u64) -> Result<Vec<u8>> {
+        let head = self.current_block_number().await?;
+        let start = (head + 1).saturating_sub(window);
+        let blocks = try_join_all((start..=head).map(|number| self.fetch_block(number))).await?;
+        blocks
+            .iter()
+            .map(|block| {
+                let blob_gas_used = block.header.blob_gas_used.unwrap_or_default();
+                u8::try_from(blob_gas_used / DATA_GAS_PER_BLOB).with_context(|| {
+                    format!("block {} blob count out of range", block.header.number)
+                })
+            })
+            .collect()
+    }
+
+    /// Returns the last Arbitrum L2 block that was produced from the given L1 block.
+    ///
+    /// Binary searches L2 blocks up to the current head, comparing the L1 block number reported
+    /// in each header. Fails with [ChainMonitorErr::NotArbitrumChain] on other chains.
+    pub async fn compute_l2_block_number(&self, l1_block: u64) -> Result<u64> {
+        if !self.chain_type().is_arbitrum() {
+            return Err(ChainMonitorErr::NotArbitrumChain.into());
+        }
+
+        let head = self.current_block_number().await?;
+        if self.l1_block_number_of(0).await? > l1_block {
+            bail!("L1 block {l1_block} precedes the first L2 block");
+        }
+        if self.l1_block_number_of(head).await? < l1_block {
+            bail!("L1 block {l1_block} has not been reached by L2 head {head}");
+        }
+
+        let (mut low, mut high) = (0, head);
+        while low < high {
+            let mid = low + (high - low).div_ceil(2);
+            if self.l1_block_number_of(mid).await? <= l1_block {
+                low = mid;
+            } else {
+                high = mid - 1;
+            }
+        }
+        Ok(low)
+    }
+
+    async fn l1_block_number_of(&self, l2_block: u64) -> Result<u64> {
+        self.fetch_arbitrum_head(l2_block)
+            .await?
+            .l1_block_number
+            .with_context(|| format!("block {l2_block} is missing the L1 block number"))
+    }
+
+    /// Fetches a block through the raw RPC interface to retain the Arbitrum-specific header
+    /// fields, which are dropped by the Ethereum block type.
+    async fn fetch_arbitrum_head(&self, number: u64) -> Result<ChainHead> {
+        let header: Option<ArbitrumHeader> = self
+            .provider
+            .client()
+            .request("eth_getBlockByNumber", (BlockNumberOrTag::Number(number), false))
+            .await
+            .with_context(|| format!("failed to fetch block {number}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let header = header.with_context(|| format!("block {number} not found"))?;
+
+        Ok(ChainHead {
+            block_number: header.number.to(),
+            block_timestamp: header.timestamp.to(),
+            block_hash: header.hash,
+            l1_block_number: Some(header.l1_block_number.to()),
+            ..Default::default()
+        })
+    }
+
+    /// Returns the mean interval between the most recent blocks.
+    ///
+    /// Falls back to the block time hint for the chain when there is not enough history.
+    pub async fn average_block_time(&self) -> Result<Duration> {
+        let intervals = self.recent_block_intervals(BLOCK_TIME_SAMPLE_SIZE).await?;
+        if intervals.is_empty() {
+            return Ok(self.block_time_hint());
+        }
+        let total: u64 = intervals.iter().sum();
+        Ok(Duration::from_secs_f64(total as f64 / intervals.len() as f64))
+    }
+
+    /// Estimates the wall-clock time at which the next block will be produced, based on the
+    /// timestamp of the current head and the average block time.
+    pub async fn estimate_next_block_time(&self) -> Result<SystemTime> {
+        let head = self.current_chain_head().await?;
+        let block_time = self.average_block_time().await?;
+        Ok(UNIX_EPOCH + Duration::from_secs(head.block_timestamp) + block_time)
+    }
+
+    /// Estimates the P10 and P90 arrival times of the next block, based on the observed
+    /// variance of recent block intervals.
+    pub async fn estimate_next_block_time_range(&self) -> Result<(SystemTime, SystemTime)> {
+        let head = self.current_chain_head().await?;
+        let head_time = UNIX_EPOCH + Duration::from_secs(head.block_timestamp);
+
+        let mut intervals = self.recent_block_intervals(BLOCK_TIME_SAMPLE_SIZE).await?;
+        if intervals.is_empty() {
+            let estimate = head_time + self.block_time_hint();
+            return Ok((estimate, estimate));
+        }
+        intervals.sort_unstable();
+        Ok((
+            head_time + Duration::from_secs(percentile(&intervals, 10)),
+            head_time + Duration::from_secs(percentile(&intervals, 90)),
+        ))
+    }
+
+    /// Returns the intervals, in seconds, between each of the last `sample_size` blocks.
+    async fn recent_block_intervals(&self, sample_size: u64) -> Result<Vec<u64>> {
+        let head = self.current_block_number().await?;
+        let start = head.saturating_sub(sample_size);
+        let blocks = try_join_all((start..=head).map(|number| self.fetch_block(number))).await?;
+        Ok(blocks
+            .windows(2)
+            .map(|pair| pair[1].header.timestamp.saturating_sub(pair[0].header.timestamp))
+            .collect())
+    }
+
+    fn block_time_hint(&self) -> Duration {
+        NamedChain::try_from(self.chain_id)
+            .ok()
+            .and_then(|chain| chain.average_blocktime_hint())
+            .unwrap_or(DEFAULT_BLOCK_TIME)
+    }
+
+    async fn fetch_block(&self, number: u64) -> Result<Block> {
+        self.provider
+            .get_block_by_number(BlockNumberOrTag::Number(number))
+            .await
+            .with_context(|| format!("failed to fetch block {number}"))
+            .map_err(ChainMonitorErr::RpcErr)?
+            .with_context(|| format!("block {number} not found"))
+    }
+
+    /// Returns whether the contract at `address` implements `interface_id`, according to its
+    /// ERC-165 `supportsInterface` function.
+    ///
+    /// Contracts that do not implement ERC-165 are reported as not supporting the interface.
+    /// Results are cached for the lifetime of the service.
+    pub async fn contract_interface_id(
+        &self,
+        address: Address,
+        interface_id: [u8; 4],
+    ) -> Result<bool> {
+        if let Some(supported) = self.interface_cache.get(&(address, interface_id)).await {
+            return Ok(supported);
+        }
+
+        let block_number = self.current_block_number().await?;
+        let call = IERC165::supportsInterfaceCall { interfaceId: interface_id.into() };
+        let supported = self
+            .try_call_at(address, call.abi_encode().into(), block_number)
+            .await?
+            .and_then(|output| IERC165::supportsInterfaceCall::abi_decode_returns(&output).ok())
+            .unwrap_or(false);
+        self.interface_cache.insert((address, interface_id), supported).await;
+        Ok(supported)
+    }
+
+    /// Returns whether the contract at `address` is a proxy, i.e. whether its EIP-1967
+    /// implementation slot (or the legacy ZeppelinOS slot) holds a non-zero address at the
+    /// current block.
+    pub async fn is_proxy_contract(&self, address: Address) -> Result<bool> {
+        let block_number = self.current_block_number().await?;
+        for slot in [EIP1967_IMPLEMENTATION_SLOT, ZEPPELINOS_IMPLEMENTATION_SLOT] {
+            let value = self
+                .provider
+                .get_storage_at(address, U256::from_be_bytes(slot.0))
+                .block_id(BlockId::number(block_number))
+                .await
+                .with_context(|| format!("failed to read storage slot {slot} of {address}"))
+                .map_err(ChainMonitorErr::RpcErr)?;
+            let implementation = Address::from_word(B256::from(value));
+            if !implementation.is_zero() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns the nonce of `address` at the `pending` block, i.e. including its transactions in
+    /// the node's mempool.
+    ///
+    /// Unlike the `latest` transaction count, this accounts for in-flight transactions, so it is
+    /// the nonce to use for the next transaction. Results are cached for 100ms.
+    pub async fn pending_nonce(&self, address: Address) -> Result<u64> {
+        if let Some(nonce) = self.pending_nonce_cache.get(&address).await {
+            return Ok(nonce);
+        }
+
+        let nonce = self
+            .provider
+            .get_transaction_count(address)
+            .pending()
+            .await
+            .with_context(|| format!("failed to fetch pending nonce of {address}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        self.pending_nonce_cache.insert(address, nonce).await;
+        Ok(nonce)
+    }
+
+    /// Reserves the next nonce of `address` for a transaction, so that concurrent submissions
+    /// from the same address are assigned distinct nonces.
+    ///
+    /// Nonces are assigned from a local counter, initialized from the [Self::pending_nonce] on
+    /// first use.
+    pub async fn nonce_safe_transfer(&self, address: Address) -> Result<NonceGuard> {
+        let counter = self.nonce_counters.lock().unwrap().entry(address).or_default().clone();
+        let initialized = counter.lock().unwrap().is_some();
+        let pending = if initialized { None } else { Some(self.pending_nonce(address).await?) };
+
+        let mut next = counter.lock().unwrap();
+        let nonce = next.or(pending).context("nonce counter is not initialized")?;
+        *next = Some(nonce + 1);
+        drop(next);
+        Ok(NonceGuard { address, nonce, counter, committed: false })
+    }
+
+    /// Returns the nonces of `addresses` at the current block, in input order.
+    ///
+    /// Nonces missing from the cache are fetched in a single JSON-RPC batch.
+    pub async fn batch_nonces(&self, addresses: Vec<Address>) -> Result<Vec<(Address, u64)>> {
+        let block_number = self.current_block_number().await?;
+        let mut nonces = Vec::with_capacity(addresses.len());
+        for address in &addresses {
+            nonces.push(self.nonce_cache.get(&(*address, block_number)).await);
+        }
+
+        let missing: Vec<Address> = addresses
+            .iter()
+            .zip(&nonces)
+            .filter_map(|(address, nonce)| nonce.is_none().then_some(*address))
+            .collect();
+        if !missing.is_empty() {
+            let mut batch = self.provider.client().new_batch();
+            let waiters = missing
+                .iter()
+                .map(|address| {
+                    batch.add_call::<_, U64>(
+                        "eth_getTransactionCount",
+                        &(*address, BlockId::number(block_number)),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .context("failed to build nonce batch request")?;
+            batch
+                .send()
+                .await
+                .context("failed to send nonce batch request")
+                .map_err(ChainMonitorErr::RpcErr)?;
+            for (address, waiter) in missing.into_iter().zip(waiters) {
+                let nonce: u64 = waiter
+                    .await
+                    .with_context(|| format!("failed to fetch nonce of {address}"))
+                    .map_err(ChainMonitorErr::RpcErr)?
+                    .to();
+                self.nonce_cache.insert((address, block_number), nonce).await;
+            }
+        }
+
+        let mut result = Vec::with_capacity(addresses.len());
+        for (address, nonce) in addresses.into_iter().zip(nonces) {
+            let nonce = match nonce {
+                Some(nonce) => nonce,
+                None => self
+                    .nonce_cache
+                    .get(&(address, block_number))
+                    .await
+                    .with_context(|| format!("nonce of {address} missing from batch"))?,
+            };
+            result.push((address, nonce));
+        }
+        Ok(result)
+    }
+
+    /// Reads the given storage `slots` of the contract at `address` at the current block,
+    /// returning `(slot, value)` pairs in input order.
+    ///
+    /// Slots missing from the cache are read in a single JSON-RPC batch.
+    pub async fn batch_storage_reads(
+        &self,
+        address: Address,
+        slots: Vec<U256>,
+    ) -> Result<Vec<(U256, U256)>> {
+        let block_number = self.current_block_number().await?;
+        let mut values = Vec::with_capacity(slots.len());
+        for slot in &slots {
+            values.push(self.storage_cache.get(&(address, *slot, block_number)).await);
+        }
+
+        let missing: Vec<U256> = slots
+            .iter()
+            .zip(&values)
+            .filter_map(|(slot, value)| value.is_none().then_some(*slot))
+            .collect();
+        if !missing.is_empty() {
+            let mut batch = self.provider.client().new_batch();
+            let waiters = missing
+                .iter()
+                .map(|slot| {
+                    batch.add_call::<_, U256>(
+                        "eth_getStorageAt",
+                        &(address, *slot, BlockId::number(block_number)),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .context("failed to build storage batch request")?;
+            batch
+                .send()
+                .await
+                .context("failed to send storage batch request")
+                .map_err(ChainMonitorErr::RpcErr)?;
+            for (slot, waiter) in missing.into_iter().zip(waiters) {
+                let value = waiter
+                    .await
+                    .with_context(|| format!("failed to read storage slot {slot} of {address}"))
+                    .map_err(ChainMonitorErr::RpcErr)?;
+                self.storage_cache.insert((address, slot, block_number), value).await;
+            }
+        }
+
+        let mut result = Vec::with_capacity(slots.len());
+        for (slot, value) in slots.into_iter().zip(values) {
+            let value = match value {
+                Some(value) => value,
+                None => self
+                    .storage_cache
+                    .get(&(address, slot, block_number))
+                    .await
+                    .with_context(|| format!("storage slot {slot} missing from batch"))?,
+            };
+            result.push((slot, value));
+        }
+        Ok(result)
+    }
+
+    /// Returns the ERC-20 `token` balance of `holder` at the current block.
+    pub async fn get_erc20_balance(&self, token: Address, holder: Address) -> Result<U256> {
+        self.erc20_call(token, IERC20::balanceOfCall { account: holder }).await
+    }
+
+    /// Returns the ERC-20 `token` amount `spender` is allowed to transfer on behalf of `owner`,
+    /// at the current block.
+    pub async fn get_erc20_allowance(
+        &self,
+        token: Address,
+        owner: Address,
+        spender: Address,
+    ) -> Result<U256> {
+        self.erc20_call(token, IERC20::allowanceCall { owner, spender }).await
+    }
+
+    /// Fetches the `Transfer` events of the ERC-20 `token` from `from_block` up to the current
+    /// head, optionally restricted to a sender and recipient.
+    pub async fn get_token_transfer_events(
+        &self,
+        token: Address,
+        from: Option<Address>,
+        to: Option<Address>,
+        from_block: u64,
+    ) -> Result<Vec<ERC20TransferEvent>> {
+        let topics = vec![
+            Some(IERC20::Transfer::SIGNATURE_HASH),
+            from.map(Address::into_word),
+            to.map(Address::into_word),
+        ];
+        let logs = self.logs_topic_filter(Some(token), topics, from_block).await?;
+
+        logs.into_iter()
+            .map(|log| {
+                let block_number = log.block_number.context("Transfer log is missing its block")?;
+                let tx_hash =
+                    log.transaction_hash.context("Transfer log is missing its transaction")?;
+                let transfer = log
+                    .log_decode::<IERC20::Transfer>()
+                    .with_context(|| format!("failed to decode Transfer log in {tx_hash}"))?
+                    .inner
+                    .data;
+                Ok(ERC20TransferEvent {
+                    from: transfer.from,
+                    to: transfer.to,
+                    value: transfer.value,
+                    block_number,
+                    tx_hash,
+                })
+            })
+            .collect()
+    }
+
+    /// Executes an ERC-20 view call returning a `uint256` at the current block, caching the
+    /// result per token, calldata and block.
+    async fn erc20_call<C: SolCall<Return = U256>>(&self, token: Address, call: C) -> Result<U256> {
+        let block_number = self.current_block_number().await?;
+        let input = Bytes::from(call.abi_encode());
+        let key = (token, input.clone(), block_number);
+        if let Some(value) = self.erc20_cache.get(&key).await {
+            return Ok(value);
+        }
+
+        let output = self.call_at(token, input, block_number).await?;
+        let value = C::abi_decode_returns(&output)
+            .with_context(|| format!("failed to decode ERC-20 response from {token}"))?;
+        self.erc20_cache.insert(key, value).await;
+        Ok(value)
+    }
+
+    /// Returns the `ProofRequested` event emitted by the proof-request contract at `contract` for
+    /// `request_id`, or `None` if the request has not been made yet.
+    ///
+    /// Found events are cached per contract and request ID.
+    pub async fn get_proof_request_event(
+        &self,
+        contract: Address,
+        request_id: U256,
+    ) -> Result<Option<ProofRequestEvent>> {
+        if let Some(event) = self.proof_request_cache.get(&(contract, request_id)).await {
+            return Ok(Some(event));
+        }
+
+        let Some((request, block_number)) = self
+            .find_request_event::<IProofRequestQueue::ProofRequested>(contract, request_id, None)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let event = ProofRequestEvent::new(request, block_number);
+        self.track_deadline(contract, request_id, event.deadline);
+        self.proof_request_cache.insert((contract, request_id), event.clone()).await;
+        Ok(Some(event))
+    }
+
+    /// Returns the `ProofFulfilled` event emitted by the proof-request contract at `contract` for
+    /// `request_id`, or `None` if the request has not been fulfilled yet.
+    ///
+    /// The event may still be reorged out until its block is final, see
+    /// [Self::finalized_block_number]. Found events are cached per contract and request ID.
+    pub async fn get_proof_fulfilled_event(
+        &self,
+        contract: Address,
+        request_id: U256,
+    ) -> Result<Option<ProofFulfilledEvent>> {
+        if let Some(event) = self.proof_fulfilled_cache.get(&(contract, request_id)).await {
+            return Ok(Some(event));
+        }
+
+        // A request cannot be fulfilled before it is made.
+        let requested_at = self
+            .proof_request_cache
+            .get(&(contract, request_id))
+            .await
+            .map(|request| request.block_number);
+        let Some((fulfillment, block_number)) = self
+            .find_request_event::<IProofRequestQueue::ProofFulfilled>(
+                contract,
+                request_id,
+                requested_at,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let event = ProofFulfilledEvent::new(fulfillment, block_number);
+        self.active_deadlines.lock().unwrap().remove(&(contract, request_id));
+        self.proof_fulfilled_cache.insert((contract, request_id), event.clone()).await;
+        Ok(Some(event))
+    }
+
+    /// Returns the number of `ProofRequested` events emitted by the proof-request contract at
+    /// `contract` from `from_block` to the current head.
+    pub async fn count_proof_requests_since(
+        &self,
+        contract: Address,
+        from_block: u64,
+    ) -> Result<u64> {
+        self.logs_count(contract, IProofRequestQueue::ProofRequested::SIGNATURE_HASH, from_block)
+            .await
+    }
+
+    /// Returns the number of `ProofFulfilled` events emitted by the proof-request contract at
+    /// `contract` from `from_block` to the current head.
+    pub async fn count_fulfilled_requests_since(
+        &self,
+        contract: Address,
+        from_block: u64,
+    ) -> Result<u64> {
+        self.logs_count(contract, IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH, from_block)
+            .await
+    }
+
+    /// Returns the ratio of fulfilled to made proof requests of the proof-request contract at
+    /// `contract` since `from_block`, or `None` if no request was made.
+    ///
+    /// The rate is also reported in [ChainMonitorStatus::fulfillment_rate].
+    pub async fn fulfillment_rate(
+        &self,
+        contract: Address,
+        from_block: u64,
+    ) -> Result<Option<f64>> {
+        let (requested, fulfilled) = tokio::try_join!(
+            self.count_proof_requests_since(contract, from_block),
+            self.count_fulfilled_requests_since(contract, from_block)
+        )?;
+        let rate = (requested > 0).then(|| fulfilled as f64 / requested as f64);
+        self.fulfillment_rate.send_replace(rate);
+        Ok(rate)
+    }
+
+    /// Returns the IDs of the requests of the proof-request contract at `contract` that have been
+    /// pending for more than `max_age_blocks` blocks, logging a warning with the age of each.
+    ///
+    /// Only requests made within the configured request lookback are considered.
+    pub async fn proof_request_backlog_alert(
+        &self,
+        contract: Address,
+        max_age_blocks: u64,
+    ) -> Result<Vec<U256>> {
+        let head = self.current_block_number().await?;
+        let mut stale = Vec::new();
+        for log in self.stale_request_logs(contract, head, max_age_blocks).await? {
+            let (Some(id), Some(block_number)) = (log_request_id(&log), log.block_number) else {
+                continue;
+            };
+            let age = head.saturating_sub(block_number);
+            tracing::warn!("Proof request {id} of {contract} is unfulfilled after {age} blocks");
+            stale.push(id);
+        }
+        Ok(stale)
+    }
+
+    /// Fetches the `ProofRequested` logs of the proof-request contract at `contract` that have
+    /// been pending for more than `max_age_blocks` blocks at `head`.
+    async fn stale_request_logs(
+        &self,
+        contract: Address,
+        head: u64,
+        max_age_blocks: u64,
+    ) -> Result<Vec<Log>> {
+        // Requests made after this block are not old enough to be stale.
+        match head.checked_sub(max_age_blocks.saturating_add(1)) {
+            Some(to_block) => self.unfulfilled_request_logs(contract, to_block).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Fetches the `ProofRequested` logs of the proof-request contract at `contract`, made up to
+    /// `to_block`, whose request has no `ProofFulfilled` log up to the current head.
+    ///
+    /// Requests are searched back to the configured request lookback, in pages of 1000 blocks.
+    async fn unfulfilled_request_logs(&self, contract: Address, to_block: u64) -> Result<Vec<Log>> {
+        let head = self.current_block_number().await?;
+        let from_block = head.saturating_sub(self.config.request_lookback_blocks);
+        let requests = topic_filter(
+            Some(contract),
+            vec![Some(IProofRequestQueue::ProofRequested::SIGNATURE_HASH)],
+        )?;
+        let fulfillments = topic_filter(
+            Some(contract),
+            vec![Some(IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH)],
+        )?;
+        let (requests, fulfillments) = tokio::try_join!(
+            self.get_logs_paged(&requests, from_block, to_block),
+            self.get_logs_paged(&fulfillments, from_block, head)
+        )?;
+
+        let fulfilled: HashSet<U256> = fulfillments.iter().filter_map(log_request_id).collect();
+        Ok(requests
+            .into_iter()
+            .filter(|log| log_request_id(log).is_some_and(|id| !fulfilled.contains(&id)))
+            .collect())
+    }
+
+    /// Schedules the unfulfilled requests of the proof-request contract at `contract` for
+    /// re-submission on the queue set with [Self::with_proof_retry_queue], returning the number
+    /// of requests scheduled.
+    ///
+    /// Used to recover submissions dropped by a restart or connectivity loss. Only the requests
+    /// reported by [Self::proof_request_backlog_alert] for the configured retry age are
+    /// considered, so that requests still being proven are not submitted twice. Requests whose
+    /// deadline is within the configured minimum deadline buffer are skipped, as a re-submission
+    /// would not land in time.
+    pub async fn retry_pending_proofs(&self, contract: Address) -> Result<u32> {
+        let Some(proof_retry_tx) = &self.proof_retry_tx else {
+            bail!("no proof retry queue configured");
+        };
+
+        let head = self.current_block_number().await?;
+        let mut retried = 0;
+        let max_age_blocks = self.config.proof_retry_min_age_blocks;
+        for log in self.stale_request_logs(contract, head, max_age_blocks).await? {
+            let request = log
+                .log_decode::<IProofRequestQueue::ProofRequested>()
+                .context("failed to decode ProofRequested log")?
+                .inner
+                .data;
+            if request.deadline <= head + self.config.min_deadline_buffer {
+                continue;
+            }
+
+            tracing::info!(
+                "Retrying proof request {} of {contract} with deadline {}",
+                request.id,
+                request.deadline
+            );
+            proof_retry_tx
+                .send(ProofRetry {
+                    contract,
+                    request_id: request.id,
+                    deadline_block: request.deadline,
+                })
+                .await
+                .context("proof retry queue closed")?;
+            retried += 1;
+        }
+        Ok(retried)
+    }
+
+    /// Returns the IDs of the proof requests whose fulfillment was dropped by `reorg`, so that they
+    /// can be re-submitted.
+    ///
+    /// Walks back the abandoned fork from its head to the common ancestor, at most the
+    /// finalization depth, collecting the `ProofFulfilled` events of its blocks. Fulfillments
+    /// that are not found again on the canonical chain are returned, and evicted from the
+    /// fulfillment cache of [Self::get_proof_fulfilled_event].
+    ///
+    /// The running service calls this on each reorg seen by [Self::subscribe_reorgs].
+    pub async fn chain_reorganization_recovery(&self, reorg: ReorgEvent) -> Result<Vec<U256>> {
+        let mut orphaned = Vec::new();
+        let mut hash = reorg.old_head.block_hash;
+        for _ in 0..=self.config.finalization_depth {
+            let block = self.get_block_by_hash(hash).await?;
+            let canonical = self
+                .provider
+                .get_block_by_number(BlockNumberOrTag::Number(block.block_number))
+                .await
+                .with_context(|| format!("failed to fetch block {}", block.block_number))
+                .map_err(ChainMonitorErr::RpcErr)?;
+            if canonical.is_some_and(|canonical| canonical.header.hash == hash) {
+                break;
+            }
+            orphaned.push(block);
+            hash = block.parent_hash;
+        }
+        let Some(fork_start) = orphaned.last().map(|block| block.block_number) else {
+            return Ok(Vec::new());
+        };
+
+        let orphaned_logs = try_join_all(orphaned.iter().map(|block| async move {
+            let filter = Filter::new()
+                .at_block_hash(block.block_hash)
+                .event_signature(IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH);
+            self.provider
+                .get_logs(&filter)
+                .await
+                .with_context(|| format!("failed to fetch logs of block {}", block.block_hash))
+                .map_err(ChainMonitorErr::RpcErr)
+        }))
+        .await?;
+
+        let mut orphaned_ids: HashMap<Address, Vec<U256>> = HashMap::new();
+        for log in orphaned_logs.iter().flatten() {
+            if let Some(id) = log_request_id(log) {
+                orphaned_ids.entry(log.address()).or_default().push(id);
+            }
+        }
+
+        // Look for the orphaned fulfillments on the canonical chain since the fork, with a single
+        // query per contract.
+        let head = self.current_block_number().await?;
+        let mut dropped = Vec::new();
+        for (contract, ids) in orphaned_ids {
+            let filter = topic_filter(
+                Some(contract),
+                vec![Some(IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH)],
+            )?;
+            let refulfilled: HashSet<U256> = if fork_start <= head {
+                self.get_logs_between(&filter, fork_start, head)
+                    .await?
+                    .iter()
+                    .filter_map(log_request_id)
+                    .collect()
+            } else {
+                HashSet::new()
+            };
+            for id in ids {
+                self.proof_fulfilled_cache.invalidate(&(contract, id)).await;
+                if !refulfilled.contains(&id) {
+                    tracing::warn!(
+                        "Fulfillment of proof request {id} of {contract} was dropped by a reorg"
+                    );
+                    dropped.push(id);
+                }
+            }
+        }
+        Ok(dropped)
+    }
+
+    /// Returns whether block `block_number` of the canonical chain contains a `ProofFulfilled`
+    /// event of request `request_id` emitted by the proof-request contract at `contract`.
+    ///
+    /// Results are cached without expiry once the block is buried under the finalization depth.
+    pub async fn block_contains_proof_fulfillment(
+        &self,
+        block_number: u64,
+        contract: Address,
+        request_id: U256,
+    ) -> Result<bool> {
+        let key = (contract, block_number, request_id);
+        if let Some(contains) = self.block_fulfillment_cache.get(&key).await {
+            return Ok(contains);
+        }
+
+        let filter = Filter::new()
+            .address(contract)
+            .event_signature(IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH)
+            .topic1(B256::from(request_id))
+            .from_block(block_number)
+            .to_block(block_number);
+        let logs = self
+            .provider
+            .get_logs(&filter)
+            .await
+            .with_context(|| format!("failed to fetch logs of block {block_number}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let contains = !logs.is_empty();
+
+        let head = self.current_block_number().await?;
+        if block_number + self.config.finalization_depth <= head {
+            self.block_fulfillment_cache.insert(key, contains).await;
+        }
+        Ok(contains)
+    }
+
+    /// Returns the number of logs emitted by `address` with `topic0` from `from_block` to the
+    /// current head, counted in pages of 1000 blocks.
+    async fn logs_count(&self, address: Address, topic0: B256, from_block: u64) -> Result<u64> {
+        let head = self.current_block_number().await?;
+        let filter = topic_filter(Some(address), vec![Some(topic0)])?;
+        let mut count = 0;
+        for (start, end) in log_pages(from_block, head) {
+            count += self.get_logs_between(&filter, start, end).await?.len() as u64;
+        }
+        Ok(count)
+    }
+
+    /// Returns the deadline block of proof request `request_id`, read from the `getRequest`
+    /// function of the proof-request contract at `contract`.
+    ///
+    /// Deadlines are immutable once a request is made, so they are cached without expiry. See
+    /// [Self::blocks_until_deadline] for the number of blocks left to fulfill the request.
+    pub async fn expected_fulfillment_deadline(
+        &self,
+        contract: Address,
+        request_id: U256,
+    ) -> Result<u64> {
+        if let Some(deadline) = self.deadline_cache.get(&(contract, request_id)).await {
+            return Ok(deadline);
+        }
+
+        let block_number = self.current_block_number().await?;
+        let call = IProofRequestQueue::getRequestCall { id: request_id };
+        let output = self.call_at(contract, call.abi_encode().into(), block_number).await?;
+        let request = IProofRequestQueue::getRequestCall::abi_decode_returns(&output)
+            .with_context(|| format!("failed to decode request {request_id} from {contract}"))?;
+        self.deadline_cache.insert((contract, request_id), request.deadline).await;
+        self.track_deadline(contract, request_id, request.deadline);
+        Ok(request.deadline)
+    }
+
+    /// Returns whether `input_hash` matches the input commitment of proof request `request_id`,
+    /// read from the `getRequestInputHash` function of the proof-request contract at `contract`
+    /// at the current block.
+    ///
+    /// Fails if the request is not found, i.e. the call reverts or returns a zero hash.
+    /// Commitments are cached per contract, request and block.
+    pub async fn validate_proof_input(
+        &self,
+        contract: Address,
+        request_id: U256,
+        input_hash: B256,
+    ) -> Result<bool> {
+        let block_number = self.current_block_number().await?;
+        let key = (contract, request_id, block_number);
+        if let Some(committed) = self.input_hash_cache.get(&key).await {
+            return Ok(committed == input_hash);
+        }
+
+        let call = IProofRequestQueue::getRequestInputHashCall { id: request_id };
+        let output = self.try_call_at(contract, call.abi_encode().into(), block_number).await?;
+        let committed = output
+            .map(|output| IProofRequestQueue::getRequestInputHashCall::abi_decode_returns(&output))
+            .transpose()
+            .with_context(|| {
+                format!("failed to decode input hash of request {request_id} from {contract}")
+            })?
+            .filter(|committed| !committed.is_zero())
+            .with_context(|| format!("proof request {request_id} not found on {contract}"))?;
+        self.input_hash_cache.insert(key, committed).await;
+        Ok(committed == input_hash)
+    }
+
+    /// Registers the deadline of a request for [Self::watch_proof_deadlines], unless the request
+    /// is already known to be fulfilled.
+    fn track_deadline(&self, contract: Address, request_id: U256, deadline: u64) {
+        if !self.proof_fulfilled_cache.contains_key(&(contract, request_id)) {
+            self.active_deadlines.lock().unwrap().insert((contract, request_id), deadline);
+        }
+    }
+
+    /// Returns the number of blocks from the current block until `deadline`, or 0 if the
+    /// deadline has passed.
+    pub async fn blocks_until_deadline(&self, deadline: u64) -> Result<u64> {
+        Ok(deadline.saturating_sub(self.current_block_number().await?))
+    }
+
+    /// Predicts the earliest range of blocks, `(from_block, to_block)` inclusive, in which the base
+    /// fee is expected to be below the 40th percentile of the last 50 blocks.
+    ///
+    /// The prediction extrapolates the least-squares trend of those base fees, and the range ends
+    /// at least the configured minimum deadline buffer before `deadline_block`. If no block is
+    /// expected to be cheap, or the chain has no base fee, the whole remaining range is returned.
+    /// Fails if the deadline is already within the buffer.
+    pub async fn optimal_submission_window(&self, deadline_block: u64) -> Result<(u64, u64)> {
+        let head = self.current_block_number().await?;
+        let last_block = deadline_block.saturating_sub(self.config.min_deadline_buffer);
+        if last_block <= head {
+            bail!(
+                "deadline block {deadline_block} is within {} blocks of the current block {head}",
+                self.config.min_deadline_buffer
+            );
+        }
+
+        let start = (head + 1).saturating_sub(SUBMISSION_WINDOW_SAMPLE_SIZE);
+        let heads = try_join_all((start..=head).map(|number| self.block_head(number))).await?;
+        let base_fees: Vec<f64> =
+            heads.iter().filter_map(|head| head.base_fee_per_gas).map(|fee| fee as f64).collect();
+        Ok(cheap_block_window(&base_fees, head, last_block).unwrap_or((head + 1, last_block)))
+    }
+
+    /// Returns the proof price reported by the `currentPrice()` function of the pricing contract
+    /// at `contract`, as of `block_number`.
+    ///
+    /// Used to audit the price paid for historical requests. Requires an archive node for blocks
+    /// older than the node's state retention. Prices are cached per contract and block.
+    pub async fn get_proof_price_at_block(
+        &self,
+        contract: Address,
+        block_number: u64,
+    ) -> Result<U256> {
+        if let Some(price) = self.proof_price_cache.get(&(contract, block_number)).await {
+            return Ok(price);
+        }
+
+        let call = IProofPricing::currentPriceCall {};
+        let output = self.call_at(contract, call.abi_encode().into(), block_number).await?;
+        let price =
+            IProofPricing::currentPriceCall::abi_decode_returns(&output).with_context(|| {
+                format!("failed to decode proof price from {contract} at block {block_number}")
+            })?;
+        self.proof_price_cache.insert((contract, block_number), price).await;
+        Ok(price)
+    }
+
+    /// Builds the transaction fulfilling request `request_id` of the proof-request contract at
+    /// `contract` with `proof` and `public_inputs`, through its `fulfillRequest` function.
+    ///
+    /// The gas limit is estimated without a sender and the fees are recommended for
+    /// [Urgency::High]. Legacy chains get a gas price instead of EIP-1559 fees.
+    pub async fn encode_proof_submission_tx(
+        &self,
+        contract: Address,
+        request_id: U256,
+        proof: Bytes,
+        public_inputs: Bytes,
+    ) -> Result<TransactionRequest> {
+        self.encode_fulfill_request_tx(contract, request_id, proof, public_inputs, Urgency::High)
+            .await
+    }
+
+    async fn encode_fulfill_request_tx(
+        &self,
+        contract: Address,
+        request_id: U256,
+        proof: Bytes,
+        public_inputs: Bytes,
+        urgency: Urgency,
+    ) -> Result<TransactionRequest> {
+        let call = IProofRequestQueue::fulfillRequestCall {
+            id: request_id,
+            proof,
+            publicInputs: public_inputs,
+        };
+        let tx = TransactionRequest::default().with_to(contract).with_input(call.abi_encode());
+        let (gas_limit, fees) =
+            tokio::try_join!(self.gas_estimate(tx.clone()), self.recommended_gas_price(urgency))?;
+
+        let tx = tx.with_gas_limit(gas_limit);
+        Ok(if self.tx_type_support().eip1559 {
+            tx.with_max_fee_per_gas(fees.max_fee_per_gas)
+                .with_max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+        } else {
+            tx.with_gas_price(fees.max_fee_per_gas)
+        })
+    }
+
+    /// Estimates the cost of fulfilling request `request_id` of the proof-request contract at
+    /// `contract` with `proof` and `public_inputs`, at the fees recommended for `urgency`.
+    ///
+    /// The gas, fee and ETH/USD price estimates are fetched concurrently. Gas is estimated for the
+    /// actual submission, so it covers the proof calldata and its verification, and the estimate
+    /// fails if the submission would revert.
+    pub async fn proof_submission_cost_estimate(
+        &self,
+        contract: Address,
+        request_id: U256,
+        proof: Bytes,
+        public_inputs: Bytes,
+        urgency: Urgency,
+    ) -> Result<CostEstimate> {
+        let call = IProofRequestQueue::fulfillRequestCall {
+            id: request_id,
+            proof,
+            publicInputs: public_inputs,
+        };
+        let tx = TransactionRequest::default().with_to(contract).with_input(call.abi_encode());
+        let (gas_used, fees, usd_per_eth) = tokio::try_join!(
+            self.gas_estimate(tx),
+            self.recommended_gas_price(urgency),
+            self.eth_usd_price()
+        )?;
+
+        let total_cost_wei = U256::from(gas_used) * U256::from(fees.max_fee_per_gas);
+        let total_cost_eth = gas_used as f64 * fees.max_fee_per_gas as f64 / 1e18;
+        let total_cost_usd = usd_per_eth.map(|usd_per_eth| total_cost_eth * usd_per_eth);
+        Ok(CostEstimate {
+            gas_used,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            priority_fee: fees.max_priority_fee_per_gas,
+            total_cost_wei,
+            total_cost_usd,
+        })
+    }
+
+    /// Dry-runs the fulfillment of request `request_id` of the proof-request contract at
+    /// `contract` with `proof` and `public_inputs` at the current block, so that invalid proofs
+    /// are caught before spending gas on them.
+    ///
+    /// Reverts are reported in the result rather than as errors, with their decoded reason or,
+    /// failing that, the node's error message.
+    pub async fn simulate_proof_submission(
+        &self,
+        contract: Address,
+        request_id: U256,
+        proof: Bytes,
+        public_inputs: Bytes,
+    ) -> Result<ProofSimulationResult> {
+        let call = IProofRequestQueue::fulfillRequestCall {
+            id: request_id,
+            proof,
+            publicInputs: public_inputs,
+        };
+        let tx = TransactionRequest::default().with_to(contract).with_input(call.abi_encode());
+        let block_number = self.current_block_number().await?;
+
+        match self.provider.call(tx.clone()).block(BlockId::number(block_number)).await {
+            Ok(_) => Ok(ProofSimulationResult {
+                success: true,
+                gas_estimate: self.gas_estimate(tx).await?,
+                revert_reason: None,
+            }),
+            Err(err) => match err.as_error_resp() {
+                Some(payload) => Ok(ProofSimulationResult {
+                    success: false,
+                    gas_estimate: 0,
+                    revert_reason: payload
+                        .as_revert_data()
+                        .and_then(|data| decode_revert_reason(&data))
+                        .or_else(|| Some(payload.message.to_string())),
+                }),
+                None => Err(ChainMonitorErr::RpcErr(err.into()).into()),
+            },
+        }
+    }
+
+    /// Dry-runs the verification of `proof` against `public_inputs` with the `verify` function of
+    /// the verifier contract at `contract`, at the current block.
+    ///
+    /// Returns `false` if the verifier rejects the proof, including by reverting.
+    pub async fn verify_proof_on_chain(
+        &self,
+        contract: Address,
+        proof: Bytes,
+        public_inputs: Bytes,
+    ) -> Result<bool> {
+        let block_number = self.current_block_number().await?;
+        let call = IProofVerifier::verifyCall { proof, publicInputs: public_inputs };
+        let Some(output) =
+            self.try_call_at(contract, call.abi_encode().into(), block_number).await?
+        else {
+            return Ok(false);
+        };
+        IProofVerifier::verifyCall::abi_decode_returns(&output)
+            .with_context(|| format!("failed to decode verification result from {contract}"))
+    }
+
+    /// Returns the event `E` emitted by `contract` with `request_id` as its first indexed topic,
+    /// along with its block number.
+    ///
+    /// Searches backwards from the head, in pages of 1000 blocks, down to `from_block` or, if
+    /// unset, the configured request lookback.
+    async fn find_request_event<E: SolEvent>(
+        &self,
+        contract: Address,
+        request_id: U256,
+        from_block: Option<u64>,
+    ) -> Result<Option<(E, u64)>> {
+        let head = self.current_block_number().await?;
+        let from_block =
+            from_block.unwrap_or_else(|| head.saturating_sub(self.config.request_lookback_blocks));
+        let filter = topic_filter(
+            Some(contract),
+            vec![Some(E::SIGNATURE_HASH), Some(B256::from(request_id))],
+        )?;
+
+        let mut found = None;
+        for (start, end) in log_pages(from_block, head).rev() {
+            found = self.get_logs_between(&filter, start, end).await?.into_iter().next();
+            if found.is_some() {
+                break;
+            }
+        }
+        let Some(log) = found else {
+            return Ok(None);
+        };
+
+        let block_number = log
+            .block_number
+            .with_context(|| format!("{} log is missing its block", E::SIGNATURE))?;
+        let event = log
+            .log_decode::<E>()
+            .with_context(|| {
+                format!("failed to decode {} log of request {request_id}", E::SIGNATURE)
+            })?
+            .inner
+            .data;
+        Ok(Some((event, block_number)))
+    }
+
+    /// Broadcasts a transaction signed offline, given as its EIP-2718 encoding, returning its
+    /// hash.
+    pub async fn broadcast_signed_tx(&self, raw_tx: Bytes) -> Result<B256> {
+        let tx_hash = self
+            .provider
+            .send_raw_transaction(&raw_tx)
+            .await
+            .map(|pending| *pending.tx_hash())
+            .context("failed to send raw transaction")
+            .map_err(ChainMonitorErr::RpcErr)?;
+        tracing::info!("Broadcast signed transaction {}", self.block_explorer_tx_url(tx_hash));
+        Ok(tx_hash)
+    }
+
+    /// Broadcasts a transaction signed offline like [Self::broadcast_signed_tx], retrying up to
+    /// `retries` times on transport errors.
+    ///
+    /// A pre-signed transaction cannot have its fees bumped, so if the node rejects it as
+    /// underpriced, fails with [ChainMonitorErr::RequiresResign] giving the max fee per gas, 10%
+    /// above the transaction's own, that it must be re-signed with.
+    pub async fn broadcast_signed_tx_with_retry(
+        &self,
+        raw_tx: Bytes,
+        retries: u32,
+    ) -> Result<B256> {
+        let envelope = TxEnvelope::decode_2718(&mut raw_tx.as_ref())
+            .context("failed to decode signed transaction")?;
+
+        let mut attempt = 0;
+        loop {
+            let err = match self.provider.send_raw_transaction(&raw_tx).await {
+                Ok(pending) => {
+                    let tx_hash = *pending.tx_hash();
+                    tracing::info!(
+                        "Broadcast signed transaction {}",
+                        self.block_explorer_tx_url(tx_hash)
+                    );
+                    return Ok(tx_hash);
+                }
+                Err(err) => err,
+            };
+
+            match err.as_error_resp() {
+                Some(payload) if is_underpriced_message(&payload.message) => {
+                    let max_fee_per_gas = envelope.max_fee_per_gas();
+                    return Err(ChainMonitorErr::RequiresResign {
+                        tx_hash: *envelope.tx_hash(),
+                        min_max_fee_per_gas: max_fee_per_gas
+                            .saturating_mul(100 + REPLACEMENT_FEE_BUMP)
+                            .div_ceil(100),
+                    }
+                    .into());
+                }
+                None if attempt < retries => {
+                    attempt += 1;
+                    tracing::warn!(
+                        "Failed to broadcast transaction {}, retrying ({attempt}/{retries}): {err}",
+                        envelope.tx_hash()
+                    );
+                    tokio::time::sleep(BROADCAST_RETRY_DELAY).await;
+                }
+                _ => {
+                    return Err(ChainMonitorErr::RpcErr(
+                        anyhow::Error::new(err).context("failed to send raw transaction"),
+                    )
+                    .into())
+                }
+            }
+        }
+    }
+
+    /// Executes a set of view calls at the current block through the Multicall3 contract,
+    /// returning the decoded result of each call in input order.
+    ///
+    /// Individual call failures are returned as errors in the result list. If Multicall3 is not
+    /// deployed on the chain, the calls are issued as concurrent `eth_call`s instead.
+    pub async fn get_multicall_results<C: SolCall>(
+        &self,
+        calls: Vec<(Address, C)>,
+    ) -> Result<Vec<Result<C::Return>>> {
+        let block_number = self.current_block_number().await?;
+        let multicall_code = self
+            .provider
+            .get_code_at(MULTICALL3_ADDRESS)
+            .block_id(BlockId::number(block_number))
+            .await
+            .context("failed to fetch Multicall3 code")
+            .map_err(ChainMonitorErr::RpcErr)?;
+
+        if multicall_code.is_empty() {
+            tracing::debug!("Multicall3 not deployed, falling back to individual eth_calls");
+            let results = join_all(calls.into_iter().map(|(target, call)| async move {
+                let output = self
+                    .try_call_at(target, call.abi_encode().into(), block_number)
+                    .await?
+                    .with_context(|| format!("call to {target} reverted"))?;
+                C::abi_decode_returns(&output)
+                    .with_context(|| format!("failed to decode response from {target}"))
+            }));
+            return Ok(results.await);
+        }
+
+        let aggregate = IMulticall3::aggregate3Call {
+            calls: calls
+                .iter()
+                .map(|(target, call)| IMulticall3::Call3 {
+                    target: *target,
+                    allowFailure: true,
+                    callData: call.abi_encode().into(),
+                })
+                .collect(),
+        };
+        let output =
+            self.call_at(MULTICALL3_ADDRESS, aggregate.abi_encode().into(), block_number).await?;
+        let results = IMulticall3::aggregate3Call::abi_decode_returns(&output)
+            .context("failed to decode Multicall3 response")?;
+        if results.len() != calls.len() {
+            bail!("Multicall3 returned {} results for {} calls", results.len(), calls.len());
+        }
+
+        Ok(results
+            .into_iter()
+            .zip(&calls)
+            .map(|(result, (target, _))| {
+                if !result.success {
+                    bail!("call to {target} failed: {}", result.returnData);
+                }
+                C::abi_decode_returns(&result.returnData)
+                    .with_context(|| format!("failed to decode response from {target}"))
+            })
+            .collect())
+    }
+
+    /// Fetches the receipt of `tx_hash`, returning `None` while the transaction is pending.
+    ///
+    /// Receipts of included transactions are cached.
+    async fn fetch_receipt(&self, tx_hash: B256) -> Result<Option<TransactionReceipt>> {
+        if let Some(receipt) = self.receipt_cache.get(&tx_hash).await {
+            return Ok(Some(receipt));
+        }
+
+        let receipt = self
+            .provider
+            .get_transaction_receipt(tx_hash)
+            .await
+            .with_context(|| format!("failed to fetch receipt for {tx_hash}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        if let Some(receipt) = &receipt {
+            self.receipt_cache.insert(tx_hash, receipt.clone()).await;
+        }
+        Ok(receipt)
+    }
+
+    /// Executes an `eth_call` to `to` at `block_number`.
+    async fn call_at(&self, to: Address, input: Bytes, block_number: u64) -> Result<Bytes> {
+        let tx = TransactionRequest::default().with_to(to).with_input(input);
+        self.provider
+            .call(tx)
+            .block(BlockId::number(block_number))
+            .await
+            .with_context(|| format!("eth_call to {to} failed at block {block_number}"))
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+
+    /// Executes an `eth_call` to `to` at `block_number`, returning `None` if the call reverts.
+    async fn try_call_at(
+        &self,
+        to: Address,
+        input: Bytes,
+        block_number: u64,
+    ) -> Result<Option<Bytes>> {
+        let tx = TransactionRequest::default().with_to(to).with_input(input);
+        match self.provider.call(tx).block(BlockId::number(block_number)).await {
+            Ok(output) => Ok(Some(output)),
+            Err(err) if err.as_error_resp().is_some() => Ok(None),
+            Err(err) => Err(ChainMonitorErr::RpcErr(err.into()).into()),
+        }
+    }
+}
+
+/// Client for a Chainlink gas price feed, for chains with a reliable on-chain gas oracle.
+pub struct GasOracleClient<P> {
+    address: Address,
+    monitor: Arc<ChainMonitorService<P>>,
+}
+
+impl<P: Provider> GasOracleClient<P> {
+    /// Returns the latest answer of the feed at the current block.
+    pub async fn latest_answer(&self) -> Result<I256> {
+        self.call(IAggregatorV3::latestAnswerCall {}).await
+    }
+
+    /// Returns the number of decimals of the feed answers.
+    pub async fn decimals(&self) -> Result<u8> {
+        self.call(IAggregatorV3::decimalsCall {}).await
+    }
+
+    async fn call<C: SolCall>(&self, call: C) -> Result<C::Return> {
+        let block_number = self.monitor.current_block_number().await?;
+        let output =
+            self.monitor.call_at(self.address, call.abi_encode().into(), block_number).await?;
+        C::abi_decode_returns(&output)
+            .with_context(|| format!("failed to decode gas oracle response from {}", self.address))
+    }
+}
+
+impl<P: Provider + Clone> ChainMonitorService<P> {
+    /// Returns a client for the Chainlink gas price feed at `oracle_address`.
+    pub fn gas_oracle_contract(&self, oracle_address: Address) -> GasOracleClient<P> {
+        GasOracleClient { address: oracle_address, monitor: Arc::new(self.clone()) }
+    }
+}
+
+/// Streams driven by chain head updates.
+impl<P> ChainMonitorService<P>
+where
+    P: Provider + Clone + 'static,
+{
+    /// Returns a stream yielding the current chain head, then each new head as it is observed.
+    ///
+    /// The chain head is only refreshed on demand, so the stream polls for updates at half the
+    /// expected block time.
+    fn head_stream(&self) -> impl Stream<Item = ChainHead> + Send + 'static {
+        let monitor = self.clone();
+        let poll_interval = self.block_time_hint() / 2;
+        stream::unfold(None, move |last_hash: Option<B256>| {
+            let monitor = monitor.clone();
+            async move {
+                loop {
+                    match monitor.current_chain_head().await {
+                        Ok(head) if last_hash != Some(head.block_hash) => {
+                            return Some((head, Some(head.block_hash)));
+                        }
+                        Ok(_) => {}
+                        Err(err) => tracing::warn!("Failed to poll chain head: {err:?}"),
+                    }
+                    tokio::time::sleep(poll_interval).await;
+                }
+            }
+        })
+    }
+
+    /// Returns a stream of the number of confirmations of `tx_hash`, yielding on each new head.
+    ///
+    /// Yields 0 while the transaction is pending, and ends after yielding a count that reaches
+    /// the configured finalization depth.
+    pub fn subscribe_confirmations(&self, tx_hash: B256) -> impl Stream<Item = u64> + Send {
+        let monitor = self.clone();
+        let finalization_depth = self.config.finalization_depth;
+        self.head_stream()
+            .then(move |head| {
+                let monitor = monitor.clone();
+                async move {
+                    match monitor.fetch_receipt(tx_hash).await {
+                        Ok(receipt) => receipt
+                            .and_then(|receipt| receipt.block_number)
+                            .map_or(0, |included| head.block_number.saturating_sub(included)),
+                        Err(err) => {
+                            tracing::warn!("Failed to fetch receipt for {tx_hash}: {err:?}");
+                            0
+                        }
+                    }
+                }
+            })
+            .scan(false, move |finalized, confirmations| {
+                if *finalized {
+                    return futures::future::ready(None);
+                }
+                *finalized = confirmations >= finalization_depth;
+                futures::future::ready(Some(confirmations))
+            })
+    }
+
+    /// Returns a stream of the number of confirmations of the block `target_hash`, yielding on
+    /// each new head.
+    ///
+    /// Yields 0 while the block is not part of the canonical chain, and ends after yielding 100
+    /// confirmations.
+    pub fn watch_block_confirmations(&self, target_hash: B256) -> impl Stream<Item = u64> + Send {
+        let monitor = self.clone();
+        self.head_stream()
+            .then(move |head| {
+                let monitor = monitor.clone();
+                async move {
+                    match monitor.canonical_block_number(target_hash).await {
+                        Ok(number) => {
+                            number.map_or(0, |number| head.block_number.saturating_sub(number))
+                        }
+                        Err(err) => {
+                            tracing::warn!("Failed to look up block {target_hash}: {err:?}");
+                            0
+                        }
+                    }
+                }
+            })
+            .scan(false, |done, confirmations| {
+                if *done {
+                    return futures::future::ready(None);
+                }
+                *done = confirmations >= MAX_WATCHED_BLOCK_CONFIRMATIONS;
+                futures::future::ready(Some(confirmations))
+            })
+    }
+
+    /// Returns the number of the block `hash`, or `None` if the block is unknown or is not part
+    /// of the canonical chain.
+    async fn canonical_block_number(&self, hash: B256) -> Result<Option<u64>> {
+        let number = match self.get_block_by_hash(hash).await {
+            Ok(head) => head.block_number,
+            Err(err) => match err.downcast_ref::<ChainMonitorErr>() {
+                Some(ChainMonitorErr::UnexpectedErr(_)) => return Ok(None),
+                _ => return Err(err),
+            },
+        };
+        let canonical = self.fetch_block(number).await?;
+        Ok((canonical.header.hash == hash).then_some(number))
+    }
+
+    /// Returns a stream of `(old_base_fee, new_base_fee)` pairs, yielding each time the base fee
+    /// of the chain head changes.
+    pub fn subscribe_base_fee_changes(&self) -> impl Stream<Item = (u128, u128)> + Send {
+        self.head_stream()
+            .filter_map(|head| futures::future::ready(head.base_fee_per_gas))
+            .scan(None, |previous: &mut Option<u128>, current| {
+                let old = previous.replace(current).unwrap_or(current);
+                futures::future::ready(Some((old, current)))
+            })
+            .filter(|(old, new)| futures::future::ready(old != new))
+    }
+
+    /// Returns a stream of the transaction count of `address`, yielding the nonce at the current
+    /// head and then each time it increases.
+    pub fn watch_account_nonce(&self, address: Address) -> impl Stream<Item = u64> + Send {
+        let monitor = self.clone();
+        self.head_stream()
+            .filter_map(move |head| {
+                let monitor = monitor.clone();
+                async move {
+                    monitor
+                        .provider
+                        .get_transaction_count(address)
+                        .block_id(BlockId::number(head.block_number))
+                        .await
+                        .inspect_err(|err| {
+                            tracing::warn!("Failed to fetch nonce of {address}: {err:?}")
+                        })
+                        .ok()
+                }
+            })
+            .filter_map({
+                let mut last_nonce = None;
+                move |nonce| {
+                    let increased = last_nonce.is_none_or(|last| nonce > last);
+                    if increased {
+                        last_nonce = Some(nonce);
+                    }
+                    futures::future::ready(increased.then_some(nonce))
+                }
+            })
+    }
+
+    /// Returns a stream of the ETH balance of `address`, yielding the balance at the current head
+    /// and then each time it changes.
+    pub fn watch_balance(&self, address: Address) -> impl Stream<Item = U256> + Send {
+        let monitor = self.clone();
+        self.head_stream()
+            .filter_map(move |head| {
+                let monitor = monitor.clone();
+                async move {
+                    monitor
+                        .provider
+                        .get_balance(address)
+                        .block_id(BlockId::number(head.block_number))
+                        .await
+                        .inspect_err(|err| {
+                            tracing::warn!("Failed to fetch balance of {address}: {err:?}")
+                        })
+                        .ok()
+                }
+            })
+            .filter_map({
+                let mut last_balance = None;
+                move |balance| {
+                    let changed = last_balance.replace(balance) != Some(balance);
+                    futures::future::ready(changed.then_some(balance))
+                }
+            })
+    }
+
+    /// Returns a stream of `(block_number, price)` pairs of the proof price reported by the
+    /// pricing contract at `contract`, yielding the price at the current head and then each time
+    /// it changes.
+    ///
+    /// Callers may delay submissions while prices rise and flush them when they fall, see
+    /// [price_increase_ratio].
+    pub fn watch_proof_prices(&self, contract: Address) -> impl Stream<Item = (u64, U256)> + Send {
+        let monitor = self.clone();
+        self.head_stream()
+            .filter_map(move |head| {
+                let monitor = monitor.clone();
+                async move {
+                    monitor
+                        .get_proof_price_at_block(contract, head.block_number)
+                        .await
+                        .inspect_err(|err| {
+                            tracing::warn!("Failed to fetch proof price of {contract}: {err:?}")
+                        })
+                        .ok()
+                        .map(|price| (head.block_number, price))
+                }
+            })
+            .filter_map({
+                let mut last_price = None;
+                move |(block_number, price)| {
+                    let changed = last_price.replace(price) != Some(price);
+                    futures::future::ready(changed.then_some((block_number, price)))
+                }
+            })
+    }
+
+    /// Returns a future resolving once the proof price reported by the pricing contract at
+    /// `contract` is at most `max_price`, see [Self::watch_proof_prices].
+    ///
+    /// Fails with [ChainMonitorErr::Timeout] if the price stays above `max_price` for `timeout`.
+    pub fn subscribe_proof_price_threshold(
+        &self,
+        contract: Address,
+        max_price: U256,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<()>> + Send {
+        let prices = self.watch_proof_prices(contract);
+        async move {
+            let mut prices = Box::pin(prices);
+            let below_threshold = async {
+                while let Some((block_number, price)) = prices.next().await {
+                    if price <= max_price {
+                        tracing::debug!(
+                            "Proof price of {contract} is {price} at block {block_number}, within {max_price}"
+                        );
+                        return Ok(());
+                    }
+                }
+                bail!("chain monitor stopped before the proof price of {contract} fell to {max_price}")
+            };
+            tokio::time::timeout(timeout, below_threshold)
+                .await
+                .map_err(|_| ChainMonitorErr::Timeout(timeout))?
+        }
+    }
+
+    /// Submits a proof fulfilling request `request_id` of the proof-request contract at
+    /// `contract`, returning the hash of the fulfillment transaction once it is final.
+    ///
+    /// If `config.pricing_contract` is set, waits for its proof price to fall to `config.max_fee`
+    /// (see [Self::subscribe_proof_price_threshold]) until the configured minimum deadline buffer
+    /// before the request's deadline. Then simulates the submission, sends it with fees for
+    /// `config.urgency` and waits for the finalization depth. Fails if the transaction reverts or
+    /// the `ProofFulfilled` event of the request is not found. When `config.retry_on_failure` is
+    /// set, the steps before sending are attempted up to 3 times; once sent, the transaction is
+    /// never re-sent.
+    pub async fn submit_proof_with_retry(
+        &self,
+        contract: Address,
+        request_id: U256,
+        proof: Bytes,
+        public_inputs: Bytes,
+        config: SubmitConfig,
+    ) -> Result<B256> {
+        let attempts = if config.retry_on_failure { MAX_SUBMISSION_ATTEMPTS } else { 1 };
+        let mut attempt = 1;
+        let tx = loop {
+            let result = self
+                .prepare_proof_submission(
+                    contract,
+                    request_id,
+                    proof.clone(),
+                    public_inputs.clone(),
+                    config,
+                )
+                .await;
+            match result {
+                Err(err) if attempt < attempts => {
+                    tracing::warn!(
+                        "Failed to prepare proof submission for request {request_id} of {contract}, retrying ({attempt}/{attempts}): {err:?}"
+                    );
+                    attempt += 1;
+                }
+                result => break result?,
+            }
+        };
+
+        let tx_hash = self.submit_transaction(tx).await?;
+        let receipt = self.wait_for_finality(tx_hash).await?;
+        if !receipt.status() {
+            bail!("proof submission {tx_hash} reverted");
+        }
+        if self.get_proof_fulfilled_event(contract, request_id).await?.is_none() {
+            bail!("proof submission {tx_hash} did not fulfill request {request_id}");
+        }
+        Ok(tx_hash)
+    }
+
+    /// Waits for the proof price, simulates the submission and encodes its transaction, see
+    /// [Self::submit_proof_with_retry].
+    async fn prepare_proof_submission(
+        &self,
+        contract: Address,
+        request_id: U256,
+        proof: Bytes,
+        public_inputs: Bytes,
+        config: SubmitConfig,
+    ) -> Result<TransactionRequest> {
+        let request = self
+            .get_proof_request_event(contract, request_id)
+            .await?
+            .with_context(|| format!("proof request {request_id} not found on {contract}"))?;
+        let blocks_left = self
+            .blocks_until_deadline(request.deadline)
+            .await?
+            .saturating_sub(self.config.min_deadline_buffer);
+        if blocks_left == 0 {
+            bail!("deadline {} of proof request {request_id} is too close", request.deadline);
+        }
+        if let Some(pricing_contract) = config.pricing_contract {
+            let price_timeout = self
+                .average_block_time()
+                .await?
+                .saturating_mul(u32::try_from(blocks_left).unwrap_or(u32::MAX));
+            self.subscribe_proof_price_threshold(pricing_contract, config.max_fee, price_timeout)
+                .await?;
+        }
+
+        let simulation = self
+            .simulate_proof_submission(contract, request_id, proof.clone(), public_inputs.clone())
+            .await?;
+        if !simulation.success {
+            bail!(
+                "proof submission for request {request_id} would revert: {}",
+                simulation.revert_reason.unwrap_or_default()
+            );
+        }
+
+        self.encode_fulfill_request_tx(contract, request_id, proof, public_inputs, config.urgency)
+            .await
+    }
+
+    /// Sends `tx` through the provider, returning its hash.
+    ///
+    /// If the sender of `tx` is set and it has no nonce, the nonce is reserved through
+    /// [Self::nonce_safe_transfer] and released if the transaction cannot be sent.
+    async fn submit_transaction(&self, mut tx: TransactionRequest) -> Result<B256> {
+        let nonce_guard = match (tx.from, tx.nonce) {
+            (Some(from), None) => {
+                let nonce_guard = self.nonce_safe_transfer(from).await?;
+                tx.set_nonce(nonce_guard.nonce());
+                Some(nonce_guard)
+            }
+            _ => None,
+        };
+
+        let tx_hash = *self
+            .provider
+            .send_transaction(tx)
+            .await
+            .context("failed to send transaction")
+            .map_err(ChainMonitorErr::RpcErr)?
+            .tx_hash();
+        if let Some(nonce_guard) = nonce_guard {
+            nonce_guard.commit();
+        }
+        tracing::info!(tx = %self.block_explorer_tx_url(tx_hash), "transaction submitted");
+        Ok(tx_hash)
+    }
+
+    /// Waits for `tx_hash` to reach the configured finalization depth, see
+    /// [Self::subscribe_confirmations], returning its receipt.
+    async fn wait_for_finality(&self, tx_hash: B256) -> Result<TransactionReceipt> {
+        let confirmations = self.subscribe_confirmations(tx_hash).collect::<Vec<_>>().await;
+        if confirmations
+            .last()
+            .is_none_or(|confirmations| *confirmations < self.config.finalization_depth)
+        {
+            bail!("chain monitor stopped before transaction {tx_hash} was final");
+        }
+        self.fetch_receipt(tx_hash)
+            .await?
+            .with_context(|| format!("receipt of transaction {tx_hash} not found"))
+    }
+
+    /// Returns a stream of whether the verifier contract at `verifier` is paused, according to its
+    /// `paused()` function, yielding the state at the current head and then each time it changes.
+    ///
+    /// Proof submissions should be halted while the verifier is paused, which is logged as an
+    /// error.
+    pub fn watch_verifier_contract_paused(
+        &self,
+        verifier: Address,
+    ) -> impl Stream<Item = bool> + Send {
+        let monitor = self.clone();
+        self.head_stream()
+            .filter_map(move |_| {
+                let monitor = monitor.clone();
+                async move {
+                    monitor
+                        .is_contract_paused(verifier, PAUSE_SELECTOR)
+                        .await
+                        .inspect_err(|err| {
+                            tracing::warn!("Failed to check whether {verifier} is paused: {err:?}")
+                        })
+                        .ok()
+                }
+            })
+            .filter_map({
+                let mut last_paused = None;
+                move |paused| {
+                    let changed = last_paused.replace(paused) != Some(paused);
+                    if changed && paused {
+                        tracing::error!("[B-VRF-PAUSED] Verifier {verifier} is paused");
+                    }
+                    futures::future::ready(changed.then_some(paused))
+                }
+            })
+    }
+
+    /// Spawns a task calling `handler` with each new log emitted by any of `contracts` whose
+    /// `topic0` is one of `topics`, using a single `eth_newFilter` log filter.
+    ///
+    /// An empty `topics` list matches all events. The task runs until the service shuts down.
+    pub fn watch_multiple_contracts<F>(
+        &self,
+        contracts: Vec<Address>,
+        topics: Vec<B256>,
+        handler: F,
+    ) -> JoinHandle<()>
+    where
+        F: Fn(Log) + Send + 'static,
+    {
+        let provider = self.provider.clone();
+        let shutdown = self.listen_for_shutdown();
+        let mut filter = Filter::new().address(contracts);
+        if !topics.is_empty() {
+            filter = filter.event_signature(topics);
+        }
+
+        tokio::spawn(async move {
+            let poller = match provider.watch_logs(&filter).await {
+                Ok(poller) => poller,
+                Err(err) => {
+                    tracing::warn!("Failed to install log filter: {err:?}");
+                    return;
+                }
+            };
+            let mut logs = poller.into_stream().flat_map(stream::iter);
+            tokio::pin!(shutdown);
+            loop {
+                tokio::select! {
+                    log = logs.next() => match log {
+                        Some(log) => handler(log),
+                        None => break,
+                    },
+                    _ = &mut shutdown => break,
+                }
+            }
+        })
+    }
+
+    /// Returns a stream of the changes to the queue of the proof-request contract at `contract`.
+    ///
+    /// New `ProofRequested` and `ProofFulfilled` events are watched with an `eth_newFilter` log
+    /// filter. Requests seen by the stream that are still unfulfilled once the chain head passes
+    /// their deadline are reported as [QueueUpdate::Expired].
+    pub fn monitor_proof_request_queue(
+        &self,
+        contract: Address,
+    ) -> impl Stream<Item = QueueUpdate> + Send {
+        let provider = self.provider.clone();
+        let filter = Filter::new().address(contract).event_signature(vec![
+            IProofRequestQueue::ProofRequested::SIGNATURE_HASH,
+            IProofRequestQueue::ProofFulfilled::SIGNATURE_HASH,
+        ]);
+        let logs = stream::once(async move { provider.watch_logs(&filter).await })
+            .filter_map(|poller| async move {
+                poller
+                    .inspect_err(|err| tracing::warn!("Failed to install log filter: {err:?}"))
+                    .ok()
+            })
+            .flat_map(|poller| poller.into_stream().flat_map(stream::iter));
+
+        // Tracks the deadline of each pending request and the last observed head.
+        let state: (HashMap<U256, u64>, u64) = Default::default();
+        stream::select(logs.map(Either::Left), self.head_stream().map(Either::Right))
+            .scan(state, |(pending, head), item| {
+                let mut updates = Vec::new();
+                match item {
+                    Either::Left(log) => match QueueUpdate::from_log(&log) {
+                        Ok(update) => {
+                            match &update {
+                                QueueUpdate::NewRequest(request) => {
+                                    pending.insert(request.id, request.deadline);
+                                }
+                                QueueUpdate::Fulfilled(fulfillment) => {
+                                    pending.remove(&fulfillment.id);
+                                }
+                                QueueUpdate::Expired { .. } => {}
+                            }
+                            updates.push(update);
+                        }
+                        Err(err) => tracing::warn!("Failed to decode queue log: {err:?}"),
+                    },
+                    Either::Right(new_head) => *head = new_head.block_number,
+                }
+                pending.retain(|id, deadline| {
+                    let expired = *deadline < *head;
+                    if expired {
+                        updates.push(QueueUpdate::Expired { id: *id });
+                    }
+                    !expired
+                });
+                futures::future::ready(Some(stream::iter(updates)))
+            })
+            .flatten()
+    }
+
+    /// Returns a stream of warnings for the requests of the proof-request contract at `contract`
+    /// whose deadline is at most `warning_blocks` blocks away, checked on each new chain head.
+    ///
+    /// Covers the requests whose deadline was looked up through
+    /// [Self::expected_fulfillment_deadline] or [Self::get_proof_request_event], until
+    /// [Self::get_proof_fulfilled_event] finds their fulfillment. Warnings are repeated on each
+    /// head until the deadline passes.
+    pub fn watch_proof_deadlines(
+        &self,
+        contract: Address,
+        warning_blocks: u64,
+    ) -> impl Stream<Item = DeadlineWarning> + Send {
+        let active_deadlines = self.active_deadlines.clone();
+        self.head_stream().flat_map(move |head| {
+            let mut warnings: Vec<_> = active_deadlines
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|((address, _), _)| *address == contract)
+                .filter_map(|((_, request_id), &deadline_block)| {
+                    let blocks_remaining = deadline_block.checked_sub(head.block_number)?;
+                    (blocks_remaining <= warning_blocks).then_some(DeadlineWarning {
+                        request_id: *request_id,
+                        deadline_block,
+                        blocks_remaining,
+                    })
+                })
+                .collect();
+            warnings.sort_by_key(|warning| warning.blocks_remaining);
+            stream::iter(warnings)
+        })
+    }
+
+    /// Returns a stream of the reorgs observed at the chain head.
+    ///
+    /// A new head is a reorg if it does not build on the previous head: its parent differs from
+    /// the previous head or, when blocks were skipped, the previous head is no longer canonical.
+    pub fn subscribe_reorgs(&self) -> impl Stream<Item = ReorgEvent> + Send {
+        let monitor = self.clone();
+        self.head_stream()
+            .scan(None, |last: &mut Option<ChainHead>, head| {
+                futures::future::ready(Some((last.replace(head), head)))
+            })
+            .filter_map(move |(last, new_head)| {
+                let monitor = monitor.clone();
+                async move {
+                    let old_head = last?;
+                    let reorged = if new_head.block_number <= old_head.block_number {
+                        true
+                    } else if new_head.block_number == old_head.block_number + 1
+                        && !new_head.parent_hash.is_zero()
+                    {
+                        new_head.parent_hash != old_head.block_hash
+                    } else {
+                        match monitor.canonical_block_number(old_head.block_hash).await {
+                            Ok(number) => number.is_none(),
+                            Err(err) => {
+                                tracing::warn!("Failed to check for a reorg: {err:?}");
+                                false
+                            }
+                        }
+                    };
+                    reorged.then_some(ReorgEvent { old_head, new_head })
+                }
+            })
+    }
+
+    /// Returns a stream of the IDs of the proof requests whose fulfillment was dropped by a reorg,
+    /// yielding after each reorg that dropped any, see [Self::chain_reorganization_recovery].
+    pub fn watch_dropped_fulfillments(&self) -> impl Stream<Item = Vec<U256>> + Send {
+        let monitor = self.clone();
+        self.subscribe_reorgs().filter_map(move |reorg| {
+            let monitor = monitor.clone();
+            async move {
+                match monitor.chain_reorganization_recovery(reorg).await {
+                    Ok(dropped) => (!dropped.is_empty()).then_some(dropped),
+                    Err(err) => {
+                        tracing::warn!("Failed to recover from reorg: {err:?}");
+                        None
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns a stream of the logs emitted by `address` between `from_block` and `to_block`
+    /// (inclusive), fetched in pages of 1000 blocks.
+    ///
+    /// Logs of each page are yielded as soon as the page is fetched, so large ranges can be
+    /// processed incrementally. A failed page yields an error and the stream continues with the
+    /// next page.
+    pub fn get_contract_events_between(
+        &self,
+        address: Address,
+        from_block: u64,
+        to_block: u64,
+    ) -> impl Stream<Item = Result<Log>> + Send + 'static {
+        let provider = self.provider.clone();
+        stream::iter(log_pages(from_block, to_block))
+            .then(move |(start, end)| {
+                let provider = provider.clone();
+                async move {
+                    let filter = Filter::new().address(address).from_block(start).to_block(end);
+                    provider
+                        .get_logs(&filter)
+                        .await
+                        .with_context(|| {
+                            format!("failed to fetch logs from block {start} to {end}")
+                        })
+                        .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+                }
+            })
+            .flat_map(|page: Result<Vec<Log>>| match page {
+                Ok(logs) => stream::iter(logs.into_iter().map(Ok).collect::<Vec<_>>()),
+                Err(err) => stream::iter(vec![Err(err)]),
+            })
+    }
+
+    /// Returns a stream of whether the node is syncing, yielding on each sync status change.
+    ///
+    /// Uses an `eth_subscribe("syncing")` subscription on providers that support it, and polls
+    /// `eth_syncing` every 10 seconds otherwise. Each status is also reflected in
+    /// [Self::is_healthy].
+    pub fn eth_subscribe_syncing(&self) -> impl Stream<Item = bool> + Send + 'static {
+        let monitor = self.clone();
+        stream::once(async move {
+            match monitor.provider.subscribe_syncing().await {
+                Ok(subscription) => subscription.into_stream().boxed(),
+                Err(err) => {
+                    tracing::debug!("Sync subscription not supported, polling instead: {err}");
+                    monitor.poll_sync_status().boxed()
+                }
+            }
+        })
+        .flatten()
+        .map(|status| matches!(status, SyncStatus::Info(_)))
+        .filter_map({
+            let syncing = self.syncing.clone();
+            let mut last_status = None;
+            move |status| {
+                syncing.send_replace(status);
+                let changed = last_status.replace(status) != Some(status);
+                futures::future::ready(changed.then_some(status))
+            }
+        })
+    }
+
+    /// Returns a stream of the `eth_syncing` status, polled every 10 seconds.
+    fn poll_sync_status(&self) -> impl Stream<Item = SyncStatus> + Send + 'static {
+        let provider = self.provider.clone();
+        stream::unfold(true, move |first| {
+            let provider = provider.clone();
+            async move {
+                if !first {
+                    tokio::time::sleep(SYNC_STATUS_POLL_INTERVAL).await;
+                }
+                loop {
+                    match provider.syncing().await {
+                        Ok(status) => return Some((status, false)),
+                        Err(err) => tracing::warn!("Failed to poll sync status: {err:?}"),
+                    }
+                    tokio::time::sleep(SYNC_STATUS_POLL_INTERVAL).await;
+                }
+            }
+        })
+    }
+
+    /// Like [Self::watch_balance], but only yields an alert for balance changes that leave the
+    /// balance of `address` below `min_balance`.
+    pub fn watch_balance_with_threshold(
+        &self,
+        address: Address,
+        min_balance: U256,
+    ) -> impl Stream<Item = BalanceAlert> + Send {
+        self.watch_balance(address).filter_map(move |balance| {
+            futures::future::ready(
+                (balance < min_balance).then_some(BalanceAlert::BelowThreshold(balance)),
+            )
+        })
+    }
+}
+
+/// Parity-style `trace_*` RPC methods.
+///
+/// These are served by Erigon, Nethermind, Reth, Anvil and the (deprecated) OpenEthereum client,
+/// but not by Geth, which only exposes the `debug_trace*` family. Trace formats vary between
+/// clients, so traces are returned as raw JSON.
+#[cfg(feature = "parity-trace")]
+impl<P: Provider> ChainMonitorService<P> {
+    /// Returns the call trace frames of a transaction, via `trace_transaction`.
+    pub async fn transaction_trace(&self, tx_hash: B256) -> Result<Vec<serde_json::Value>> {
+        self.provider
+            .client()
+            .request("trace_transaction", (tx_hash,))
+            .await
+            .with_context(|| format!("failed to trace transaction {tx_hash}"))
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+
+    /// Returns the call trace frames of every transaction in a block, via `trace_block`.
+    pub async fn block_trace(&self, block_number: u64) -> Result<Vec<serde_json::Value>> {
+        self.provider
+            .client()
+            .request("trace_block", (BlockNumberOrTag::Number(block_number),))
+            .await
+            .with_context(|| format!("failed to trace block {block_number}"))
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+}
+
+/// Geth-style `debug_trace*` RPC methods.
+#[cfg(feature = "debug-rpc")]
+impl<P: Provider> ChainMonitorService<P> {
+    /// Returns the top-level call frame of every transaction in a block, in block order, via
+    /// `debug_traceBlockByNumber` with the `callTracer`.
+    pub async fn get_block_trace(&self, block_number: u64) -> Result<Vec<CallTrace>> {
+        #[derive(Deserialize)]
+        struct TransactionTrace {
+            result: CallTrace,
+        }
+
+        if let Some(traces) = self.block_trace_cache.get(&block_number).await {
+            return Ok(traces);
+        }
+
+        let traces: Vec<TransactionTrace> = self
+            .provider
+            .client()
+            .request(
+                "debug_traceBlockByNumber",
+                (
+                    BlockNumberOrTag::Number(block_number),
+                    serde_json::json!({ "tracer": "callTracer" }),
+                ),
+            )
+            .await
+            .with_context(|| format!("failed to trace block {block_number}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        let traces: Vec<CallTrace> = traces.into_iter().map(|trace| trace.result).collect();
+        self.block_trace_cache.insert(block_number, traces.clone()).await;
+        Ok(traces)
+    }
+}
+
+#[cfg(feature = "storage-layout")]
+impl<P: Provider> ChainMonitorService<P> {
+    /// Returns the storage layout of the contract at `address`, read from the Hardhat/Foundry
+    /// artifacts in the configured storage layout directory.
+    ///
+    /// The contract is identified by the hash of its deployed code, so contracts with immutables
+    /// are only matched if the artifact holds the linked runtime code. Artifacts must have been
+    /// compiled with the `storageLayout` output selection.
+    pub async fn contract_storage_layout(&self, address: Address) -> Result<serde_json::Value> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Artifact {
+            deployed_bytecode: DeployedBytecode,
+            storage_layout: serde_json::Value,
+        }
+
+        /// Foundry nests the bytecode in an object, while Hardhat stores it as a string.
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum DeployedBytecode {
+            Foundry { object: Bytes },
+            Hardhat(Bytes),
+        }
+
+        let dir = self
+            .config
+            .storage_layout_dir
+            .as_ref()
+            .context("no storage layout directory configured")?;
+        let code = self
+            .provider
+            .get_code_at(address)
+            .await
+            .with_context(|| format!("failed to fetch code of {address}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        if code.is_empty() {
+            bail!("no contract deployed at {address}");
+        }
+        let code_hash = keccak256(&code);
+
+        let mut dirs = vec![dir.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = tokio::fs::read_dir(&dir)
+                .await
+                .with_context(|| format!("failed to read {}", dir.display()))?;
+            while let Some(entry) = entries.next_entry().await? {
+                let path = entry.path();
+                if entry.file_type().await?.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+                if path.extension().is_none_or(|ext| ext != "json") {
+                    continue;
+                }
+                let Ok(artifact) =
+                    serde_json::from_slice::<Artifact>(&tokio::fs::read(&path).await?)
+                else {
+                    continue;
+                };
+                let (DeployedBytecode::Foundry { object: bytecode }
+                | DeployedBytecode::Hardhat(bytecode)) = artifact.deployed_bytecode;
+                if keccak256(bytecode) == code_hash {
+                    tracing::debug!("Resolved {address} to artifact {}", path.display());
+                    return Ok(artifact.storage_layout);
+                }
+            }
+        }
+        bail!("no artifact in {} matches the code of {address}", dir.display())
+    }
+}
+
+/// Flashbots bundle RPC methods, served by Flashbots-compatible relays and builders.
+#[cfg(feature = "flashbots")]
+impl<P: Provider> ChainMonitorService<P> {
+    /// Simulates `txs` as a bundle on top of the current head via `eth_callBundle`, returning the
+    /// result of each transaction in bundle order.
+    pub async fn simulate_bundle(
+        &self,
+        txs: Vec<TransactionRequest>,
+    ) -> Result<Vec<SimulationResult>> {
+        let block_number = self.current_block_number().await?;
+        self.simulate_bundle_at(txs, block_number).await
+    }
+
+    async fn simulate_bundle_at(
+        &self,
+        txs: Vec<TransactionRequest>,
+        block_number: u64,
+    ) -> Result<Vec<SimulationResult>> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CallBundleParams {
+            txs: Vec<TransactionRequest>,
+            block_number: U64,
+            state_block_number: BlockNumberOrTag,
+        }
+
+        #[derive(Deserialize)]
+        struct CallBundleResponse {
+            results: Vec<SimulationResult>,
+        }
+
+        let num_txs = txs.len();
+        let params = CallBundleParams {
+            txs,
+            block_number: U64::from(block_number + 1),
+            state_block_number: BlockNumberOrTag::Number(block_number),
+        };
+        let response: CallBundleResponse = self
+            .provider
+            .client()
+            .request("eth_callBundle", (params,))
+            .await
+            .with_context(|| format!("failed to simulate bundle at block {block_number}"))
+            .map_err(ChainMonitorErr::RpcErr)?;
+        if response.results.len() != num_txs {
+            bail!(
+                "bundle simulation returned {} results for {num_txs} transactions",
+                response.results.len()
+            );
+        }
+        Ok(response.results)
+    }
+}
+
+#[cfg(feature = "etherscan")]
+impl<P> ChainMonitorService<P> {
+    /// Fetches the verified ABI of the contract at `address` from the configured block explorer,
+    /// returned as the raw ABI JSON string.
+    ///
+    /// Results are cached for the lifetime of the service.
+    pub async fn get_contract_abi_from_etherscan(&self, address: Address) -> Result<String> {
+        #[derive(Deserialize)]
+        struct ExplorerResponse {
+            status: String,
+            result: String,
+        }
+
+        if let Some(abi) = self.abi_cache.get(&address).await {
+            return Ok(abi);
+        }
+
+        let api_key = match &self.config.etherscan_api_key {
+            Some(key) => key.clone(),
+            None => std::env::var("ETHERSCAN_API_KEY")
+                .context("no block explorer API key configured")?,
+        };
+        let response: ExplorerResponse = reqwest::Client::new()
+            .get(&self.config.etherscan_api_url)
+            .query(&[
+                ("chainid", self.chain_id.to_string()),
+                ("module", "contract".to_string()),
+                ("action", "getabi".to_string()),
+                ("address", address.to_string()),
+                ("apikey", api_key),
+            ])
+            .send()
+            .await
+            .and_then(|res| res.error_for_status())
+            .with_context(|| format!("failed to query block explorer for ABI of {address}"))
+            .map_err(ChainMonitorErr::RpcErr)?
+            .json()
+            .await
+            .context("failed to parse block explorer response")?;
+        if response.status != "1" {
+            bail!("block explorer returned no ABI for {address}: {}", response.result);
+        }
+
+        self.abi_cache.insert(address, response.result.clone()).await;
+        Ok(response.result)
+    }
+}
+
+impl<P> ChainMonitorService<P> {
+    /// Returns the block explorer URL of a transaction.
+    pub fn block_explorer_tx_url(&self, tx_hash: B256) -> String {
+        format!("{}/tx/{tx_hash}", self.explorer_base_url())
+    }
+
+    /// Returns the block explorer URL of an address.
+    pub fn block_explorer_address_url(&self, address: Address) -> String {
+        format!("{}/address/{address}", self.explorer_base_url())
+    }
+
+    /// Returns the block explorer URL of a block.
+    pub fn block_explorer_block_url(&self, block_number: u64) -> String {
+        format!("{}/block/{block_number}", self.explorer_base_url())
+    }
+
+    /// Returns the configured block explorer URL, or the known explorer of the chain, without a
+    /// trailing slash.
+    ///
+    /// Empty if the chain has no known explorer, so that links are relative paths rather than
+    /// pointing at the explorer of another chain.
+    fn explorer_base_url(&self) -> &str {
+        match &self.config.chain_explorer_url {
+            Some(url) => url.trim_end_matches('/'),
+            None => NamedChain::try_from(self.chain_id)
+                .ok()
+                .and_then(|chain| chain.etherscan_urls())
+                .map_or("", |(_, base_url)| base_url.trim_end_matches('/')),
+        }
+    }
+
+    /// Returns whether `blocks` form a chain, i.e. whether the parent hash of each block matches
+    /// the hash of the block before it.
+    pub fn is_canonical_chain(blocks: &[ChainHead]) -> bool {
+        blocks.windows(2).all(|pair| pair[1].parent_hash == pair[0].block_hash)
+    }
+
+    /// Attempts to decode every log in the receipt as the event `E`, preserving log order.
+    pub fn decode_receipt_logs<E: SolEvent>(receipt: &TransactionReceipt) -> Vec<Result<E>> {
+        receipt
+            .inner
+            .logs()
+            .iter()
+            .map(|log| {
+                E::decode_log_data(log.data())
+                    .map_err(|err| ChainMonitorErr::UnexpectedErr(err.into()).into())
+            })
+            .collect()
+    }
+
+    /// Decodes the first log in the receipt whose `topic0` matches the event `E`.
+    ///
+    /// Returns `None` if the receipt has no log for `E`.
+    pub fn decode_first_log<E: SolEvent>(receipt: &TransactionReceipt) -> Result<Option<E>> {
+        receipt
+            .inner
+            .logs()
+            .iter()
+            .find(|log| log.topic0() == Some(&E::SIGNATURE_HASH))
+            .map(|log| {
+                E::decode_log_data(log.data())
+                    .map_err(|err| ChainMonitorErr::UnexpectedErr(err.into()).into())
+            })
+            .transpose()
+    }
+
+    /// Decodes the calldata of `tx` as a call to `C`.
+    ///
+    /// Fails if the calldata does not start with the selector of `C` or its arguments do not
+    /// decode.
+    pub fn decode_input_data<C: SolCall>(tx: &Transaction) -> Result<C> {
+        if !Self::selector_matches::<C>(tx) {
+            return Err(ChainMonitorErr::UnexpectedErr(anyhow::anyhow!(
+                "calldata is not a call to {}",
+                C::SIGNATURE
+            ))
+            .into());
+        }
+        C::abi_decode_raw(&tx.input()[4..])
+            .map_err(|err| ChainMonitorErr::UnexpectedErr(err.into()).into())
+    }
+
+    /// Returns whether the calldata of `tx` starts with the selector of `C`, without decoding the
+    /// call arguments.
+    pub fn selector_matches<C: SolCall>(tx: &Transaction) -> bool {
+        tx.input().starts_with(&C::SELECTOR)
+    }
+}
+
+/// Decodes block extra data as UTF-8, removing the null bytes clients pad it with.
+fn decode_extra_data(data: &[u8]) -> Option<String> {
+    std::str::from_utf8(data).ok().map(|text| text.replace('\0', ""))
+}
+
+/// Returns the EIP-2718 encoding of a receipt, as stored in the receipts trie.
+fn encode_receipt(receipt: &TransactionReceipt) -> Vec<u8> {
+    receipt.clone().into_primitives_receipt().inner.encoded_2718()
+}
+
+/// Returns the least-squares slope of `values` against their indices.
+fn least_squares_slope(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = values.iter().sum::<f64>() / n;
+    let (covariance, variance) =
+        values.iter().enumerate().fold((0.0, 0.0), |(cov, var), (x, y)| {
+            let dx = x as f64 - mean_x;
+            (cov + dx * (y - mean_y), var + dx * dx)
+        });
+    if variance == 0.0 {
+        0.0
+    } else {
+        covariance / variance
+    }
+}
+
+/// Returns the earliest range of blocks after `head`, up to `last_block`, whose base fee
+/// extrapolated from the trend of `base_fees` (ending at `head`) is below their 40th percentile.
+fn cheap_block_window(base_fees: &[f64], head: u64, last_block: u64) -> Option<(u64, u64)> {
+    if base_fees.is_empty() {
+        return None;
+    }
+    let mut sorted = base_fees.to_vec();
+    sorted.sort_unstable_by(f64::total_cmp);
+    let threshold = percentile(&sorted, SUBMISSION_WINDOW_PERCENTILE);
+
+    // Evaluate the least-squares line through the base fees, indexed from 0, at future blocks.
+    let slope = least_squares_slope(base_fees);
+    let mean_x = (base_fees.len() - 1) as f64 / 2.0;
+    let mean_y = base_fees.iter().sum::<f64>() / base_fees.len() as f64;
+    let is_cheap = |block: u64| {
+        let x = (base_fees.len() - 1) as f64 + (block - head) as f64;
+        mean_y + slope * (x - mean_x) < threshold
+    };
+
+    let from_block = (head + 1..=last_block).find(|block| is_cheap(*block))?;
+    let to_block = (from_block..=last_block).take_while(|block| is_cheap(*block)).last()?;
+    Some((from_block, to_block))
+}
+
+/// Returns the number of blocks a transaction paying `gas_price` waits for inclusion, assuming
+/// it tips `priority_fee` and the base fee falls from `next_base_fee` at its maximum rate of
+/// 12.5% per block. Chains without a base fee include it in the next block.
+fn confirmation_blocks(next_base_fee: Option<u64>, gas_price: u128, priority_fee: u128) -> u64 {
+    let Some(next_base_fee) = next_base_fee else {
+        return 1;
+    };
+    let mut base_fee = next_base_fee as u128;
+    let mut blocks = 1;
+    while base_fee.saturating_add(priority_fee) > gas_price && blocks < MAX_CONFIRMATION_BLOCKS {
+        base_fee = base_fee * 7 / 8;
+        blocks += 1;
+    }
+    blocks
+}
+
+/// Returns the time taken by `blocks` blocks of `block_time`, saturating at [Duration::MAX].
+fn blocks_duration(block_time: Duration, blocks: u64) -> Duration {
+    block_time.saturating_mul(u32::try_from(blocks).unwrap_or(u32::MAX))
+}
+
+/// Returns the exponential moving average of `values`, oldest first, with a smoothing factor of
+/// `2 / (n + 1)`.
+fn exponential_moving_average(values: &[f64]) -> f64 {
+    let alpha = 2.0 / (values.len() as f64 + 1.0);
+    values.iter().skip(1).fold(values.first().copied().unwrap_or_default(), |ema, value| {
+        alpha * value + (1.0 - alpha) * ema
+    })
+}
+
+/// Returns whether a node error message rejects a transaction for paying too little to replace
+/// another, or to enter the mempool at all.
+fn is_underpriced_message(message: &str) -> bool {
+    let message = message.to_lowercase();
+    message.contains("underpriced") || message.contains("fee too low")
+}
+
+/// Returns the relative change from the `old` to the `new` proof price, e.g. `0.1` for a 10%
+/// increase and a negative ratio for a decrease.
+///
+/// An increase from a zero price is infinite.
+pub fn price_increase_ratio(old: U256, new: U256) -> f64 {
+    if old.is_zero() {
+        return if new.is_zero() { 0.0 } else { f64::INFINITY };
+    }
+    (f64::from(new) - f64::from(old)) / f64::from(old)
+}
+
+/// Computes the intrinsic gas of a transaction: the base cost, calldata cost and contract
+/// creation cost, without an RPC call.
+///
+/// Access list and init code word costs are not included, so this is a lower bound on the
+/// gas charged before execution.
+pub fn intrinsic_gas(tx: &TransactionRequest) -> u64 {
+    let calldata_gas: u64 = tx
+        .input
+        .input()
+        .map(|input| {
+            input
+                .iter()
+                .map(|byte| if *byte == 0 { ZERO_BYTE_GAS } else { NON_ZERO_BYTE_GAS })
+                .sum()
+        })
+        .unwrap_or_default();
+    let creation_gas =
+        if matches!(tx.to, Some(TxKind::Call(_))) { 0 } else { CONTRACT_CREATION_GAS };
+    SIMPLE_TRANSFER_GAS + calldata_gas + creation_gas
+}
+
+/// Computes the address of a contract deployed by `deployer` with `CREATE2`, i.e. the last 20
+/// bytes of `keccak256(0xff ++ deployer ++ salt ++ init_code_hash)`.
+pub fn compute_create2_address(deployer: Address, salt: B256, init_code_hash: B256) -> Address {
+    deployer.create2(salt, init_code_hash)
+}
+
+/// Computes the address of a contract deployed by `deployer` with `CREATE` at `nonce`, i.e.
+/// the last 20 bytes of `keccak256(rlp([deployer, nonce]))`.
+pub fn compute_create_address(deployer: Address, nonce: u64) -> Address {
+    deployer.create(nonce)
+}
+
+/// Estimates the gas refunded to a transaction that used `gas_used` gas and pre-warmed
+/// `access_list_slots` storage slots, capped at a fifth of the gas used (EIP-3529).
+fn refund_estimate(gas_used: u64, access_list_slots: u64) -> u64 {
+    (gas_used / MAX_REFUND_QUOTIENT)
+        .min(access_list_slots.saturating_mul(ACCESS_LIST_SLOT_REFUND_GAS))
+}
+
+/// Splits the block range `from_block..=to_block` into pages of at most 1000 blocks, oldest
+/// first.
+fn log_pages(from_block: u64, to_block: u64) -> impl DoubleEndedIterator<Item = (u64, u64)> {
+    let pages = to_block.checked_sub(from_block).map_or(0, |span| span / LOG_PAGE_SIZE + 1);
+    (0..pages).map(move |page| {
+        let start = from_block + page * LOG_PAGE_SIZE;
+        (start, to_block.min(start + LOG_PAGE_SIZE - 1))
+    })
+}
+
+/// Returns the request ID of a proof-request contract log, its first indexed topic.
+fn log_request_id(log: &Log) -> Option<U256> {
+    log.topics().get(1).map(|topic| U256::from_be_bytes(topic.0))
+}
+
+/// Builds a [Filter] matching the logs emitted by `address`, if set, with the given indexed
+/// topics, starting with `topic0`. `None` entries match any topic.
+fn topic_filter(address: Option<Address>, topics: Vec<Option<B256>>) -> Result<Filter> {
+    if topics.len() > 4 {
+        bail!("logs have at most 4 topics, got {}", topics.len());
+    }
+
+    let mut filter = Filter::new();
+    if let Some(address) = address {
+        filter = filter.address(address);
+    }
+    for (i, topic) in topics.into_iter().enumerate() {
+        if let Some(topic) = topic {
+            filter.topics[i] = topic.into();
+        }
+    }
+    Ok(filter)
+}
+
+/// Returns the `pct` percentile (nearest rank) of a non-empty, sorted slice.
+fn percentile<T: Copy>(sorted: &[T], pct: usize) -> T {
+    sorted[(sorted.len() - 1) * pct / 100]
+}
+
+impl<P: Provider> ChainMonitorService<P> {
+    /// Fetches the latest block, with its full transactions if configured, and the gas price.
+    async fn poll_chain_state(&self) -> Result<(Block, u128), ChainMonitorErr> {
+        let mut block_request = self.provider.get_block_by_number(BlockNumberOrTag::Latest);
+        if self.config.cache_full_block {
+            block_request = block_request.full();
+        }
+        let (block_res, gas_price_res) = tokio::join!(block_request, self.provider.get_gas_price());
+
+        let block = block_res
+            .context("failed to latest block")
+            .map_err(ChainMonitorErr::RpcErr)?
+            .context("failed to fetch latest block: no block in response")
+            .map_err(ChainMonitorErr::UnexpectedErr)?;
+        let gas_price =
+            gas_price_res.context("failed to get gas price").map_err(ChainMonitorErr::RpcErr)?;
+        Ok((block, gas_price))
+    }
+}
+
+#[async_trait]
+impl<P: Provider> ChainMonitor for ChainMonitorService<P> {
+    async fn current_block_number(&self) -> Result<u64> {
+        ChainMonitorService::current_block_number(self).await
+    }
+
+    fn cached_block_number(&self) -> u64 {
+        self.head_update.borrow().block_number
+    }
+
+    async fn call_at(&self, to: Address, input: Bytes, block_number: u64) -> Result<Bytes> {
+        ChainMonitorService::call_at(self, to, input, block_number).await
+    }
+
+    async fn get_logs(&self, filter: &Filter) -> Result<Vec<Log>> {
+        self.provider
+            .get_logs(filter)
+            .await
+            .context("failed to fetch logs")
+            .map_err(|err| ChainMonitorErr::RpcErr(err).into())
+    }
+
+    fn config(&self) -> &ChainMonitorConfig {
+        &self.config
+    }
+
+    fn explorer_base_url(&self) -> &str {
+        ChainMonitorService::explorer_base_url(self)
+    }
+
+    fn block_explorer_tx_url(&self, tx_hash: B256) -> String {
+        ChainMonitorService::block_explorer_tx_url(self, tx_hash)
+    }
+
+    fn block_explorer_address_url(&self, address: Address) -> String {
+        ChainMonitorService::block_explorer_address_url(self, address)
+    }
+
+    async fn recorded_queue_depth(&self, contract: Address, block_number: u64) -> Option<u64> {
+        self.queue_depth_cache.get(&(contract, block_number)).await
+    }
+
+    async fn record_queue_depth(&self, contract: Address, block_number: u64, depth: u64) {
+        self.queue_depth_cache.insert((contract, block_number), depth).await;
+        self.queue_depth.send_replace(Some(depth));
+    }
+}
+
+impl<P> RetryTask for ChainMonitorService<P>
+where
+    P: Provider + 'static + Clone,
+{
+    type Error = ChainMonitorErr;
+    fn spawn(&self, cancel_token: CancellationToken) -> RetryRes<Self::Error> {
+        let self_clone = self.clone();
+
+        Box::pin(async move {
+            tracing::info!("Starting ChainMonitor service");
+
+            let chain_id = self_clone
+                .provider
+                .get_chain_id()
+                .await
+                .context("failed to get chain ID")
+                .map_err(ChainMonitorErr::UnexpectedErr)
+                .map_err(SupervisorErr::Recover)?;
+
+            // OPTIMIZATION: Use 1ms interval for maximum speed instead of block-based timing
+            let chain_poll_time = Duration::from_millis(1);
+            // Original: NamedChain::try_from(chain_id).ok().and_then(|chain| chain.average_blocktime_hint()).map(|block_time| block_time.mul_f32(0.6)).unwrap_or(Duration::from_secs(2));
+
+            // Recover from reorgs while this task runs; the recovery logs the dropped fulfillments.
+            let reorg_watch = cancel_token.child_token();
+            let _reorg_watch_guard = reorg_watch.clone().drop_guard();
+            let mut dropped_fulfillments = Box::pin(self_clone.watch_dropped_fulfillments());
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        dropped = dropped_fulfillments.next() => if dropped.is_none() { break },
+                        _ = reorg_watch.cancelled() => break,
+                    }
+                }
+            });
+
+            loop {
+                tokio::select! {
+                    // Wait for notification or handle cancellation
+                    _ = self_clone.update_notifier.notified() => {
+                        // Needs update, lock next update value to avoid unnecessary notifications.
+                        let mut next_update = self_clone.next_update.write().await;
+
+                        match self_clone.poll_chain_state().await {
+                            Ok((block, gas_price)) => {
+                                let head = ChainHead::from_block(&block);
+                                self_clone.latest_block.send_replace(Some(Arc::new(block)));
+                                tracing::trace!(
+                                    has_withdrawals = head.has_withdrawals,
+                                    "Updated chain head to block {}",
+                                    head.block_number
+                                );
+                                let _ = self_clone.head_update.send_replace(head);
+                                let _ = self_clone.gas_price.send_replace(gas_price);
+                                self_clone.poll_errors.lock().unwrap().reset();
+                                // Requests past their deadline no longer need to be watched.
+                                self_clone
+                                    .active_deadlines
+                                    .lock()
+                                    .unwrap()
+                                    .retain(|_, deadline| *deadline >= head.block_number);
+
+                                // Set timestamp for next update
+                                *next_update = Instant::now() + chain_poll_time;
+                            }
+                            Err(err) => {
+                                let mut errors = self_clone.poll_errors.lock().unwrap();
+                                let alerting = errors.is_alert_threshold_exceeded();
+                                errors.record();
+                                if !alerting && errors.is_alert_threshold_exceeded() {
+                                    tracing::error!(
+                                        "[B-CHM-ALT-1] More than {} chain monitor errors within {:?}, last error: {:?}",
+                                        self_clone.config.error_alert_threshold,
+                                        self_clone.config.error_alert_window,
+                                        err
+                                    );
+                                }
+                                // Hand the pending update over to the restarted task, so that
+                                // callers waiting on the head or gas price are not left hanging.
+                                self_clone.update_notifier.notify_one();
+                                return Err(SupervisorErr::Recover(err));
+                            }
+                        }
+                    }
+                    // Handle cancellation
+                    _ = cancel_token.cancelled() => {
+                        tracing::debug!("Chain monitor received cancellation, shutting down gracefully");
+                        self_clone.shutdown.cancel();
+                        break;
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        network::{EthereumWallet, TransactionBuilder},
+        node_bindings::{Anvil, AnvilInstance},
+        primitives::Bytes,
+        providers::{
+            ext::AnvilApi,
+            fillers::{
+                BlobGasFiller, ChainIdFiller, FillProvider, GasFiller, JoinFill, NonceFiller,
+                WalletFiller,
+            },
+            Identity, ProviderBuilder, RootProvider,
+        },
+        rpc::types::TransactionRequest,
+        signers::local::PrivateKeySigner,
+    };
+
+    use super::*;
+
+    alloy::sol! {
+        event Ping();
+    }
+
+    // Init code for a contract whose runtime emits three LOG1 entries, using the first 32 bytes
+    // of calldata as topic0.
+    const TRIPLE_LOG_INIT_CODE: &str =
+        "6015600c60003960156000f36000358060006000a18060006000a160006000a100";
+
+    // Init code for a contract whose runtime emits a LOG2 entry, using the first two 32-byte words
+    // of calldata as topic0 and topic1.
+    const TOPIC_LOG_INIT_CODE: &str = "600c600c600039600c6000f360203560003560006000a200";
+
+    // Init code for a contract whose runtime emits a LOG2 entry, using the first two 32-byte words
+    // of calldata as topic0 and topic1 and the rest of calldata as the log data.
+    const DATA_LOG_INIT_CODE: &str =
+        "6015600c60003960156000f360403603806040600037602035600035826000a200";
+
+    // Init code for a contract whose runtime returns the current block number as a 32-byte word,
+    // whatever the calldata.
+    const BLOCK_NUMBER_INIT_CODE: &str = "6009600c60003960096000f34360005260206000f3";
+
+    // Init code for a contract whose runtime returns `true` as a 32-byte word, whatever the
+    // calldata.
+    const RETURN_TRUE_INIT_CODE: &str = "600a600c600039600a6000f3600160005260206000f3";
+
+    // Init code for a contract whose runtime always reverts with empty data.
+    const REVERT_INIT_CODE: &str = "6005600c60003960056000f360006000fd";
+
+    type TestProvider = FillProvider<
+        JoinFill<
+            JoinFill<
+                Identity,
+                JoinFill<GasFiller, JoinFill<BlobGasFiller, JoinFill<NonceFiller, ChainIdFiller>>>,
+            >,
+            WalletFiller<EthereumWallet>,
+        >,
+        RootProvider,
+    >;
+
+    type TestMonitor = ChainMonitorService<TestProvider>;
+
+    async fn deploy_contract(provider: &impl Provider, init_code: &str) -> Address {
+        let tx = TransactionRequest::default()
+            .with_deploy_code(Bytes::from(hex::decode(init_code).unwrap()));
+        let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        receipt.contract_address.unwrap()
+    }
+
+    async fn setup_monitor(
+        init_code: &str,
+    ) -> (AnvilInstance, Arc<TestProvider>, Arc<TestMonitor>, Address) {
+        setup_monitor_with(init_code, |chain_monitor| chain_monitor).await
+    }
+
+    // Spawns Anvil, deploys a contract from `init_code` with its first account and starts a chain
+    // monitor over it, after `configure` is applied to the monitor.
+    async fn setup_monitor_with(
+        init_code: &str,
+        configure: impl FnOnce(TestMonitor) -> TestMonitor,
+    ) -> (AnvilInstance, Arc<TestProvider>, Arc<TestMonitor>, Address) {
+        let anvil = Anvil::new().spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = Arc::new(
+            ProviderBuilder::new()
+                .wallet(EthereumWallet::from(signer))
+                .connect(&anvil.endpoint())
+                .await
+                .unwrap(),
+        );
+
+        let contract = deploy_contract(&provider, init_code).await;
+        let chain_monitor =
+            Arc::new(configure(ChainMonitorService::new(provider.clone()).await.unwrap()));
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+        (anvil, provider, chain_monitor, contract)
+    }
+
+    // Emits `event` from a contract deployed from DATA_LOG_INIT_CODE, and refreshes the chain head
+    // of `chain_monitor` to include it.
+    async fn emit_event<E: SolEvent>(
+        chain_monitor: &TestMonitor,
+        contract: Address,
+        event: &E,
+    ) -> TransactionReceipt {
+        let [topic0, topic1] = event.encode_topics_array::<2>();
+        let input = [topic0.0.as_slice(), topic1.0.as_slice(), &event.encode_data()].concat();
+        let tx = TransactionRequest::default().with_to(contract).with_input(input);
+        let receipt =
+            chain_monitor.provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        *chain_monitor.next_update.write().await = Instant::now();
+        receipt
+    }
+
+    fn proof_requested(id: u64, deadline: u64) -> IProofRequestQueue::ProofRequested {
+        IProofRequestQueue::ProofRequested {
+            id: U256::from(id),
+            requester: Address::repeat_byte(0x11),
+            inputHash: B256::repeat_byte(0x22),
+            deadline,
+            maxPrice: U256::from(1_000_000),
+        }
+    }
+
+    fn proof_fulfilled(id: u64) -> IProofRequestQueue::ProofFulfilled {
+        IProofRequestQueue::ProofFulfilled {
+            id: U256::from(id),
+            prover: Address::repeat_byte(0x33),
+            proofHash: B256::repeat_byte(0x44),
+        }
+    }
+
+    #[tokio::test]
+    async fn chain_monitor_smoke_test() {
+        // Using an unknown chain ID to use default 2s polling time.
+        let anvil = Anvil::new().chain_id(888833888).spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = Arc::new(
+            ProviderBuilder::new()
+                .wallet(EthereumWallet::from(signer))
+                .connect(&anvil.endpoint())
+                .await
+                .unwrap(),
+        );
+
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let block = chain_monitor.current_block_number().await.unwrap();
+        assert_eq!(block, 0);
+
+        const NUM_BLOCKS: u64 = 10;
+
+        provider.anvil_mine(Some(NUM_BLOCKS), Some(2)).await.unwrap();
+
+        // Block should still be 0 until the next polling interval.
+        let block = chain_monitor.current_block_number().await.unwrap();
+        assert_eq!(block, 0);
+
+        // Update next update time to now, to allow querying the block number from chain.
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let block = chain_monitor.current_block_number().await.unwrap();
+        assert_eq!(block, NUM_BLOCKS);
+    }
+
+    #[tokio::test]
+    async fn has_withdrawals_follows_chain_head() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+        chain_monitor.current_block_number().await.unwrap();
+
+        // Anvil blocks do not process any withdrawals.
+        assert!(!chain_monitor.has_withdrawals());
+    }
+
+    #[test]
+    fn chain_type_from_chain_id() {
+        assert_eq!(ChainType::from(1), ChainType::Ethereum);
+        assert_eq!(ChainType::from(42161), ChainType::ArbitrumOne);
+        assert_eq!(ChainType::from(42170), ChainType::ArbitrumNova);
+        assert_eq!(ChainType::from(10), ChainType::OptimismMainnet);
+        assert_eq!(ChainType::from(8453), ChainType::Base);
+        assert_eq!(ChainType::from(137), ChainType::Polygon);
+        assert_eq!(ChainType::from(888833888), ChainType::Other(888833888));
+        assert!(ChainType::from(421614).is_arbitrum());
+    }
+
+    #[test]
+    fn base_fee_trend_classifies_slope() {
+        assert_eq!(least_squares_slope(&[10.0, 13.0, 16.0, 19.0]), 3.0);
+        assert_eq!(
+            BaseFeeTrend::from_slope(least_squares_slope(&[10.0, 13.0, 16.0])),
+            BaseFeeTrend::Rising(3.0)
+        );
+        assert_eq!(
+            BaseFeeTrend::from_slope(least_squares_slope(&[20.0, 15.0, 10.0])),
+            BaseFeeTrend::Falling(-5.0)
+        );
+        assert_eq!(
+            BaseFeeTrend::from_slope(least_squares_slope(&[10.0, 11.0, 10.0])),
+            BaseFeeTrend::Stable
+        );
+    }
+
+    #[test]
+    fn urgency_max_base_fee_compounds_per_block() {
+        assert_eq!(Urgency::Low.max_base_fee(800), 900);
+        assert_eq!(Urgency::Medium.max_base_fee(800), 1_138);
+        assert!(Urgency::High.max_base_fee(800) > Urgency::Medium.max_base_fee(800));
+        assert_eq!(Urgency::High.max_base_fee(u128::MAX), u128::MAX);
+    }
+
+    #[test]
+    fn price_increase_ratio_is_relative() {
+        let ratio = |old: u64, new: u64| price_increase_ratio(U256::from(old), U256::from(new));
+        assert_eq!(ratio(100, 110), 0.1);
+        assert_eq!(ratio(100, 50), -0.5);
+        assert_eq!(ratio(100, 100), 0.0);
+        assert_eq!(ratio(0, 0), 0.0);
+        assert_eq!(ratio(0, 1), f64::INFINITY);
+    }
+
+    #[test]
+    fn cheap_block_window_follows_base_fee_trend() {
+        // Falling fees become cheap after the sampled blocks.
+        let falling: Vec<f64> = (0..10).map(|i| 100.0 - i as f64).collect();
+        assert_eq!(cheap_block_window(&falling, 9, 20), Some((10, 20)));
+        // Rising fees only get more expensive.
+        let rising: Vec<f64> = (0..10).map(|i| 100.0 + i as f64).collect();
+        assert_eq!(cheap_block_window(&rising, 9, 20), None);
+        // Flat fees are never below their own percentile.
+        assert_eq!(cheap_block_window(&[100.0; 10], 9, 20), None);
+        assert_eq!(cheap_block_window(&[], 9, 20), None);
+    }
+
+    #[test]
+    fn confirmation_blocks_waits_for_base_fee_to_fall() {
+        assert_eq!(confirmation_blocks(None, 0, 0), 1);
+        assert_eq!(confirmation_blocks(Some(100), 110, 10), 1);
+        assert_eq!(confirmation_blocks(Some(128), 122, 10), 2);
+        assert_eq!(confirmation_blocks(Some(100), 0, 0), MAX_CONFIRMATION_BLOCKS);
+    }
+
+    #[test]
+    fn blocks_duration_saturates() {
+        assert_eq!(blocks_duration(Duration::from_secs(2), 3), Duration::from_secs(6));
+        assert_eq!(
+            blocks_duration(Duration::from_secs(2), u64::MAX),
+            Duration::from_secs(2) * u32::MAX
+        );
+        assert_eq!(blocks_duration(Duration::MAX, 2), Duration::MAX);
+    }
+
+    #[test]
+    fn log_pages_cover_block_range() {
+        assert_eq!(log_pages(0, 0).collect::<Vec<_>>(), [(0, 0)]);
+        assert_eq!(log_pages(500, 2_499).collect::<Vec<_>>(), [(500, 1_499), (1_500, 2_499)]);
+        assert_eq!(log_pages(0, 1_000).rev().collect::<Vec<_>>(), [(1_000, 1_000), (0, 999)]);
+        assert_eq!(log_pages(10, 9).count(), 0);
+    }
+
+    #[test]
+    fn exponential_moving_average_weights_recent_values() {
+        assert_eq!(exponential_moving_average(&[]), 0.0);
+        assert_eq!(exponential_moving_average(&[10.0; 5]), 10.0);
+        assert_eq!(exponential_moving_average(&[10.0, 20.0, 30.0]), 22.5);
+    }
+
+    #[test]
+    fn refund_estimate_is_capped_by_gas_used() {
+        assert_eq!(refund_estimate(100_000, 0), 0);
+        assert_eq!(refund_estimate(100_000, 2), 4_800);
+        assert_eq!(refund_estimate(100_000, 100), 20_000);
+    }
+
+    #[test]
+    fn decode_extra_data_strips_null_bytes() {
+        assert_eq!(decode_extra_data(b"geth\0\0"), Some("geth".to_string()));
+        assert_eq!(
+            decode_extra_data(b"Titan (titanbuilder.xyz)"),
+            Some("Titan (titanbuilder.xyz)".to_string())
+        );
+        assert_eq!(decode_extra_data(&[0xd8, 0x83, 0xff]), None);
+    }
+
+    #[test]
+    fn tx_type_support_follows_header_fields() {
+        let legacy = alloy::consensus::Header::default();
+        assert_eq!(
+            TxTypeSupport::from_header(&legacy),
+            TxTypeSupport { legacy: true, ..Default::default() }
+        );
+
+        let london = alloy::consensus::Header { base_fee_per_gas: Some(7), ..Default::default() };
+        assert_eq!(
+            TxTypeSupport::from_header(&london),
+            TxTypeSupport { legacy: true, eip2930: true, eip1559: true, eip4844: false }
+        );
+
+        let cancun = alloy::consensus::Header { blob_gas_used: Some(0), ..london };
+        assert!(TxTypeSupport::from_header(&cancun).eip4844);
+    }
+
+    #[test]
+    fn compute_deployment_addresses() {
+        // Example 1 of EIP-1014.
+        assert_eq!(
+            compute_create2_address(Address::ZERO, B256::ZERO, keccak256([0x00])),
+            address!("4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38")
+        );
+        assert_eq!(
+            compute_create_address(address!("6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0"), 0),
+            address!("cd234a471b72ba2f1ccf0a70fcaba648a5eecd8d")
+        );
+    }
+
+    #[test]
+    fn intrinsic_gas_counts_calldata_and_creation() {
+        let to = Address::repeat_byte(0x11);
+        let transfer = TransactionRequest::default().with_to(to);
+        assert_eq!(intrinsic_gas(&transfer), 21_000);
+
+        let call = TransactionRequest::default().with_to(to).with_input(vec![0, 1, 0, 2]);
+        assert_eq!(intrinsic_gas(&call), 21_000 + 2 * 4 + 2 * 16);
+
+        let deploy = TransactionRequest::default().with_deploy_code(vec![0xff]);
+        assert_eq!(intrinsic_gas(&deploy), 21_000 + 16 + 32_000);
+    }
+
+    #[tokio::test]
+    async fn slippage_protected_gas_price_rejects_spikes() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider.clone()).await.unwrap();
+
+        let current = provider.get_gas_price().await.unwrap();
+        assert_eq!(chain_monitor.slippage_protected_gas_price(current, 0).await.unwrap(), current);
+        assert_eq!(
+            chain_monitor.slippage_protected_gas_price(current * 9 / 10, 2_000).await.unwrap(),
+            current
+        );
+
+        let err = chain_monitor.slippage_protected_gas_price(current / 2, 5_000).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ChainMonitorErr>(),
+            Some(ChainMonitorErr::GasPriceTooHigh { current: c, .. }) if *c == current
+        ));
+    }
+
+    #[tokio::test]
+    async fn max_proof_price_for_budget_divides_budget() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+
+        let budget = U256::from(1_000_000_000_000_000u64);
+        assert_eq!(
+            chain_monitor.max_proof_price_for_budget(budget, 100_000).await.unwrap(),
+            10_000_000_000
+        );
+        assert_eq!(
+            chain_monitor.max_proof_price_for_budget(U256::MAX, 1).await.unwrap(),
+            u128::MAX
+        );
+    }
+
+    #[tokio::test]
+    async fn max_proof_price_for_budget_rejects_zero_gas_estimate() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+
+        assert!(chain_monitor.max_proof_price_for_budget(U256::from(1_000), 0).await.is_err());
+        assert!(chain_monitor.max_proof_price_for_budget(U256::ZERO, 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn max_proof_price_for_budget_is_capped_at_absolute_max_fee() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor =
+            ChainMonitorService::new(provider).await.unwrap().with_config(ChainMonitorConfig {
+                absolute_max_fee_per_gas: Some(5_000_000_000),
+                ..Default::default()
+            });
+
+        let budget = U256::from(1_000_000_000_000_000u64);
+        assert_eq!(
+            chain_monitor.max_proof_price_for_budget(budget, 100_000).await.unwrap(),
+            5_000_000_000
+        );
+        assert_eq!(
+            chain_monitor.max_proof_price_for_budget(U256::MAX, 1).await.unwrap(),
+            5_000_000_000
+        );
+        // Prices below the cap are left as is.
+        assert_eq!(
+            chain_monitor.max_proof_price_for_budget(budget, 1_000_000).await.unwrap(),
+            1_000_000_000
+        );
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_for_deployment_covers_gas_used() {
+        let anvil = Anvil::new().spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = Arc::new(
+            ProviderBuilder::new()
+                .wallet(EthereumWallet::from(signer))
+                .connect(&anvil.endpoint())
+                .await
+                .unwrap(),
+        );
+        let chain_monitor = ChainMonitorService::new(provider.clone()).await.unwrap();
+
+        let bytecode = Bytes::from(hex::decode(TRIPLE_LOG_INIT_CODE).unwrap());
+        let estimate = chain_monitor
+            .estimate_gas_for_deployment(bytecode.clone(), Bytes::new())
+            .await
+            .unwrap();
+
+        let tx = TransactionRequest::default().with_deploy_code(bytecode);
+        let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        assert!(estimate >= receipt.gas_used);
+    }
+
+    #[tokio::test]
+    async fn log_filter_from_abi_uses_cached_head() {
+        alloy::sol! {
+            event Ping(uint256 indexed value);
+        }
+
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        provider.anvil_mine(Some(5), None).await.unwrap();
+
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+        let head = chain_monitor.current_block_number().await.unwrap();
+
+        let address = Address::repeat_byte(0x11);
+        let filter = chain_monitor.log_filter_from_abi::<Ping>(address, 1);
+        assert_eq!(filter.get_from_block(), Some(1));
+        assert_eq!(filter.get_to_block(), Some(head));
+        assert!(filter.address.matches(&address));
+        assert!(filter.topics[0].matches(&Ping::SIGNATURE_HASH));
+    }
+
+    #[tokio::test]
+    async fn filter_recent_logs_decodes_events_in_range() {
+        let (_anvil, _provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+        for id in 1..=3 {
+            emit_event(&chain_monitor, contract, &proof_fulfilled(id)).await;
+        }
+
+        // Each event is emitted in its own block, so the last two blocks hold the last two.
+        let filter = Filter::new().address(contract);
+        let logs = chain_monitor.filter_recent_logs(filter, 2).await.unwrap();
+        let ids: Vec<U256> = logs
+            .iter()
+            .map(|log| {
+                chain_monitor.decode_log::<IProofRequestQueue::ProofFulfilled>(log).unwrap().id
+            })
+            .collect();
+        assert_eq!(ids, [U256::from(2), U256::from(3)]);
+        assert!(chain_monitor.decode_log::<IProofRequestQueue::ProofRequested>(&logs[0]).is_err());
+    }
+
+    #[tokio::test]
+    async fn event_abi_to_filter_parses_signature() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+
+        let filter = chain_monitor
+            .event_abi_to_filter(
+                "Transfer(address indexed from, address indexed to, uint256 value)",
+                None,
+                0,
+            )
+            .unwrap();
+        let expected = alloy::primitives::keccak256("Transfer(address,address,uint256)");
+        assert!(filter.topics[0].matches(&expected));
+        assert!(filter.address.is_empty());
+
+        assert!(chain_monitor.event_abi_to_filter("Transfer(address", None, 0).is_err());
+    }
+
+    #[tokio::test]
+    async fn logs_topic_filter_matches_multiple_topics() {
+        let (_anvil, provider, chain_monitor, emitter) = setup_monitor(TOPIC_LOG_INIT_CODE).await;
+        let topic0 = B256::repeat_byte(0x01);
+        for topic1 in [B256::repeat_byte(0x02), B256::repeat_byte(0x03)] {
+            let input = [topic0.as_slice(), topic1.as_slice()].concat();
+            let tx = TransactionRequest::default().with_to(emitter).with_input(input);
+            provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        }
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let logs =
+            chain_monitor.logs_topic_filter(Some(emitter), vec![Some(topic0)], 0).await.unwrap();
+        assert_eq!(logs.len(), 2);
+
+        let logs = chain_monitor
+            .logs_topic_filter(Some(emitter), vec![Some(topic0), Some(B256::repeat_byte(0x03))], 0)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics()[1], B256::repeat_byte(0x03));
+
+        assert!(chain_monitor.logs_topic_filter(None, vec![None; 5], 0).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn get_proof_request_event_decodes_log() {
+        let (_anvil, _provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+        let request = proof_requested(7, 1000);
+        let receipt = emit_event(&chain_monitor, contract, &request).await;
+
+        let event = chain_monitor.get_proof_request_event(contract, request.id).await.unwrap();
+        assert_eq!(
+            event,
+            Some(ProofRequestEvent {
+                id: request.id,
+                requester: request.requester,
+                input_hash: request.inputHash,
+                deadline: request.deadline,
+                max_price: request.maxPrice,
+                block_number: receipt.block_number.unwrap(),
+            })
+        );
+        assert_eq!(
+            chain_monitor.get_proof_request_event(contract, U256::from(8)).await.unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn get_proof_fulfilled_event_decodes_log() {
+        let (_anvil, _provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+        let fulfillment = proof_fulfilled(7);
+        assert_eq!(
+            chain_monitor.get_proof_fulfilled_event(contract, fulfillment.id).await.unwrap(),
+            None
+        );
+
+        let receipt = emit_event(&chain_monitor, contract, &fulfillment).await;
+        let event =
+            chain_monitor.get_proof_fulfilled_event(contract, fulfillment.id).await.unwrap();
+        assert_eq!(
+            event,
+            Some(ProofFulfilledEvent {
+                id: fulfillment.id,
+                prover: fulfillment.prover,
+                proof_hash: fulfillment.proofHash,
+                block_number: receipt.block_number.unwrap(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn get_proof_price_at_block_queries_historical_state() {
+        let (_anvil, provider, chain_monitor, pricing) =
+            setup_monitor(BLOCK_NUMBER_INIT_CODE).await;
+        provider.anvil_mine(Some(3), None).await.unwrap();
+
+        for block_number in [2, 4] {
+            assert_eq!(
+                chain_monitor.get_proof_price_at_block(pricing, block_number).await.unwrap(),
+                U256::from(block_number)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn fulfillment_rate_counts_events() {
+        let (_anvil, provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+        emit_event(&chain_monitor, contract, &proof_requested(0, 1000)).await;
+        emit_event(&chain_monitor, contract, &proof_requested(1, 1000)).await;
+        emit_event(&chain_monitor, contract, &proof_fulfilled(1)).await;
+
+        assert_eq!(chain_monitor.count_proof_requests_since(contract, 0).await.unwrap(), 2);
+        assert_eq!(chain_monitor.count_fulfilled_requests_since(contract, 0).await.unwrap(), 1);
+        assert_eq!(chain_monitor.fulfillment_rate(contract, 0).await.unwrap(), Some(0.5));
+        assert_eq!(chain_monitor.status().fulfillment_rate, Some(0.5));
+
+        // Only request 0 is left unfulfilled.
+        provider.anvil_mine(Some(5), None).await.unwrap();
+        *chain_monitor.next_update.write().await = Instant::now();
+        assert_eq!(
+            chain_monitor.proof_request_backlog_alert(contract, 4).await.unwrap(),
+            vec![U256::ZERO]
+        );
+    }
+
+    #[tokio::test]
+    async fn block_contains_proof_fulfillment_matches_block_and_request() {
+        let (_anvil, _provider, chain_monitor, contract) =
+            setup_monitor_with(DATA_LOG_INIT_CODE, |chain_monitor| {
+                chain_monitor
+                    .with_config(ChainMonitorConfig { finalization_depth: 0, ..Default::default() })
+            })
+            .await;
+        let receipt = emit_event(&chain_monitor, contract, &proof_fulfilled(5)).await;
+        let block_number = receipt.block_number.unwrap();
+
+        let contains = |block_number: u64, id: u64| {
+            chain_monitor.block_contains_proof_fulfillment(block_number, contract, U256::from(id))
+        };
+        assert!(contains(block_number, 5).await.unwrap());
+        assert!(!contains(block_number, 6).await.unwrap());
+        assert!(!contains(block_number - 1, 5).await.unwrap());
+        assert_eq!(
+            chain_monitor
+                .block_fulfillment_cache
+                .get(&(contract, block_number, U256::from(5)))
+                .await,
+            Some(true)
+        );
+    }
+
+    #[tokio::test]
+    async fn validate_proof_input_compares_commitment() {
+        // Every request commits to the input hash 0x00..01.
+        let (_anvil, provider, chain_monitor, queue) = setup_monitor(RETURN_TRUE_INIT_CODE).await;
+        let missing = deploy_contract(&provider, REVERT_INIT_CODE).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let id = U256::from(1);
+        let committed = B256::with_last_byte(1);
+        assert!(chain_monitor.validate_proof_input(queue, id, committed).await.unwrap());
+        assert!(!chain_monitor.validate_proof_input(queue, id, B256::ZERO).await.unwrap());
+        assert!(chain_monitor.validate_proof_input(missing, id, committed).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn retry_pending_proofs_schedules_requests_before_deadline() {
+        let (retry_tx, mut retries) = mpsc::channel(8);
+        let (_anvil, provider, chain_monitor, contract) =
+            setup_monitor_with(DATA_LOG_INIT_CODE, |chain_monitor| {
+                chain_monitor
+                    .with_config(ChainMonitorConfig {
+                        proof_retry_min_age_blocks: 2,
+                        ..Default::default()
+                    })
+                    .with_proof_retry_queue(retry_tx)
+            })
+            .await;
+        // Request 1 is pending, request 2 is too close to its deadline and request 3 is fulfilled.
+        for (id, deadline) in [(1, 1000), (2, 10), (3, 1000)] {
+            emit_event(&chain_monitor, contract, &proof_requested(id, deadline)).await;
+        }
+        emit_event(&chain_monitor, contract, &proof_fulfilled(3)).await;
+        provider.anvil_mine(Some(3), None).await.unwrap();
+        // Request 4 was just made, so it may still be being proven.
+        emit_event(&chain_monitor, contract, &proof_requested(4, 1000)).await;
+
+        assert_eq!(chain_monitor.retry_pending_proofs(contract).await.unwrap(), 1);
+        assert_eq!(
+            retries.try_recv().unwrap(),
+            ProofRetry { contract, request_id: U256::from(1), deadline_block: 1000 }
+        );
+        assert!(retries.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn watch_proof_deadlines_warns_near_deadline() {
+        let (_anvil, _provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+        // The requests are made in blocks 2 and 3, so only request 1 is due within 10 blocks.
+        for (id, deadline) in [(1, 5), (2, 102)] {
+            emit_event(&chain_monitor, contract, &proof_requested(id, deadline)).await;
+        }
+        for id in [1, 2] {
+            chain_monitor.get_proof_request_event(contract, U256::from(id)).await.unwrap().unwrap();
+        }
+
+        let mut warnings = Box::pin(chain_monitor.watch_proof_deadlines(contract, 10));
+        let warning = warnings.next().await.unwrap();
+        assert_eq!(
+            warning,
+            DeadlineWarning { request_id: U256::from(1), deadline_block: 5, blocks_remaining: 2 }
+        );
+
+        // Deadlines are no longer tracked once they pass.
+        chain_monitor.provider.anvil_mine(Some(3), None).await.unwrap();
+        *chain_monitor.next_update.write().await = Instant::now();
+        chain_monitor.current_block_number().await.unwrap();
+        let active_deadlines = chain_monitor.active_deadlines.lock().unwrap();
+        assert!(!active_deadlines.contains_key(&(contract, U256::from(1))));
+        assert!(active_deadlines.contains_key(&(contract, U256::from(2))));
+    }
+
+    #[tokio::test]
+    async fn estimate_proof_latency_includes_overhead_and_finality() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        provider.anvil_mine(Some(3), Some(1)).await.unwrap();
+        let chain_monitor =
+            Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap().with_config(
+                ChainMonitorConfig {
+                    finalization_depth: 2,
+                    proof_generation_overhead: Duration::from_secs(60),
+                    ..Default::default()
+                },
+            ));
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let latency =
+            chain_monitor.estimate_proof_latency(Address::ZERO, U256::from(1)).await.unwrap();
+        let block_time = chain_monitor.average_block_time().await.unwrap();
+        assert!(latency >= Duration::from_secs(60) + block_time * 3);
+
+        assert_eq!(chain_monitor.estimate_time_to_finality().await.unwrap(), block_time * 2);
+        // A gas price far above the base fee is included in the next block.
+        assert_eq!(
+            chain_monitor.estimate_confirmation_time(u128::MAX / 2).await.unwrap(),
+            block_time
+        );
+    }
+
+    #[tokio::test]
+    async fn fee_market_summary_reads_fee_market() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        provider.anvil_mine(Some(5), None).await.unwrap();
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let summary = chain_monitor.fee_market_summary().await.unwrap();
+        assert!(summary.legacy_gas_price > 0);
+        assert!(summary.base_fee.is_some());
+        assert!(summary.priority_fee_p50 <= summary.priority_fee_p75);
+        assert!(summary.priority_fee_p75 <= summary.priority_fee_p95);
+        assert!(summary.blob_base_fee.is_some());
+        assert_eq!(summary.gas_utilization, 0.0);
+        // Empty blocks lower the base fee, by less than the trend threshold.
+        assert_eq!(summary.base_fee_trend, BaseFeeTrend::Stable);
+        assert!(summary.next_base_fee_estimate < summary.base_fee);
+    }
+
+    #[tokio::test]
+    async fn gas_tracker_report_reads_fee_market() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        provider.anvil_mine(Some(5), None).await.unwrap();
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let report = chain_monitor.gas_tracker_report().await.unwrap();
+        assert!(report.current_base_fee.is_some());
+        assert!(report.current_gas_price > 0);
+        assert!(report.gas_price_ema > 0);
+        assert!(report.gas_price_24h_avg.is_some());
+        assert!(report.priority_fee_p50 <= report.priority_fee_p75);
+        assert!(report.blob_base_fee.is_some());
+        assert_eq!(report.chain_util_pct, 0.0);
+        assert!(report.estimated_confirmation_blocks >= 1);
+    }
+
+    #[tokio::test]
+    async fn broadcast_signed_tx_requires_resign_when_underpriced() {
+        let anvil = Anvil::new().arg("--no-mining").spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let wallet = EthereumWallet::from(signer.clone());
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider.clone()).await.unwrap();
+
+        let sign = |value: u64| {
+            let tx = TransactionRequest::default()
+                .with_from(signer.address())
+                .with_to(Address::ZERO)
+                .with_value(U256::from(value))
+                .with_nonce(0)
+                .with_chain_id(anvil.chain_id())
+                .with_gas_limit(21_000)
+                .with_max_fee_per_gas(10_000_000_000)
+                .with_max_priority_fee_per_gas(1_000_000_000);
+            let wallet = wallet.clone();
+            async move { Bytes::from(tx.build(&wallet).await.unwrap().encoded_2718()) }
+        };
+
+        let raw_tx = sign(1).await;
+        let tx_hash = chain_monitor.broadcast_signed_tx(raw_tx.clone()).await.unwrap();
+        assert_eq!(tx_hash, keccak256(&raw_tx));
+
+        // Replacing the pending transaction with the same fees is rejected.
+        let replacement = sign(2).await;
+        let err = chain_monitor.broadcast_signed_tx_with_retry(replacement, 3).await.unwrap_err();
+        match err.downcast::<ChainMonitorErr>().unwrap() {
+            ChainMonitorErr::RequiresResign { min_max_fee_per_gas, .. } => {
+                assert_eq!(min_max_fee_per_gas, 11_000_000_000)
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn encode_proof_submission_tx_populates_fees() {
+        let (_anvil, _provider, chain_monitor, contract) =
+            setup_monitor(RETURN_TRUE_INIT_CODE).await;
+        // Poll the head so that EIP-1559 support is detected.
+        chain_monitor.current_block_number().await.unwrap();
+
+        let tx = chain_monitor
+            .encode_proof_submission_tx(
+                contract,
+                U256::from(7),
+                Bytes::from_static(b"proof"),
+                Bytes::from_static(b"inputs"),
+            )
+            .await
+            .unwrap();
+        let call =
+            IProofRequestQueue::fulfillRequestCall::abi_decode(tx.input.input().unwrap()).unwrap();
+        assert_eq!(call.id, U256::from(7));
+        assert_eq!(call.proof, Bytes::from_static(b"proof"));
+        assert!(tx.gas.unwrap() > SIMPLE_TRANSFER_GAS);
+        assert!(tx.max_fee_per_gas.unwrap() >= tx.max_priority_fee_per_gas.unwrap());
+        assert!(tx.gas_price.is_none());
+    }
+
+    #[tokio::test]
+    async fn simulate_proof_submission_reports_reverts() {
+        let (_anvil, provider, chain_monitor, accepting) =
+            setup_monitor(RETURN_TRUE_INIT_CODE).await;
+        let reverting = deploy_contract(&provider, REVERT_INIT_CODE).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let (proof, public_inputs) = (Bytes::from_static(b"proof"), Bytes::from_static(b"inputs"));
+        let result = chain_monitor
+            .simulate_proof_submission(
+                accepting,
+                U256::from(1),
+                proof.clone(),
+                public_inputs.clone(),
+            )
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert!(result.gas_estimate > SIMPLE_TRANSFER_GAS);
+
+        let result = chain_monitor
+            .simulate_proof_submission(reverting, U256::from(1), proof, public_inputs)
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert_eq!(result.gas_estimate, 0);
+    }
+
+    #[tokio::test]
+    async fn proof_submission_cost_estimate_bounds_cost() {
+        let (_anvil, provider, chain_monitor, accepting) =
+            setup_monitor(RETURN_TRUE_INIT_CODE).await;
+        let reverting = deploy_contract(&provider, REVERT_INIT_CODE).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let estimate = |contract: Address, proof: Bytes| {
+            chain_monitor.proof_submission_cost_estimate(
+                contract,
+                U256::from(1),
+                proof,
+                Bytes::from_static(b"inputs"),
+                Urgency::Medium,
+            )
+        };
+        let small = estimate(accepting, Bytes::from_static(b"proof")).await.unwrap();
+        assert!(small.max_fee_per_gas >= small.priority_fee);
+        assert_eq!(
+            small.total_cost_wei,
+            U256::from(small.gas_used) * U256::from(small.max_fee_per_gas)
+        );
+        // No price feed is configured.
+        assert_eq!(small.total_cost_usd, None);
+
+        // The proof calldata is paid for.
+        let large = estimate(accepting, Bytes::from(vec![0xff; 1024])).await.unwrap();
+        assert!(large.gas_used >= small.gas_used + 1000 * 16);
+
+        assert!(estimate(reverting, Bytes::from_static(b"proof")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_proof_on_chain_treats_reverts_as_rejections() {
+        let (_anvil, provider, chain_monitor, accepting) =
+            setup_monitor(RETURN_TRUE_INIT_CODE).await;
+        let reverting = deploy_contract(&provider, REVERT_INIT_CODE).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let (proof, public_inputs) = (Bytes::from_static(b"proof"), Bytes::from_static(b"inputs"));
+        assert!(chain_monitor
+            .verify_proof_on_chain(accepting, proof.clone(), public_inputs.clone())
+            .await
+            .unwrap());
+        assert!(!chain_monitor
+            .verify_proof_on_chain(reverting, proof, public_inputs)
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn monitor_proof_request_queue_tracks_requests() {
+        let (_anvil, _provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+
+        let updates =
+            chain_monitor.monitor_proof_request_queue(contract).take(4).collect::<Vec<_>>();
+        let emitter = chain_monitor.clone();
+        tokio::spawn(async move {
+            // Give the stream time to install its log filter.
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            emit_event(&emitter, contract, &proof_requested(1, 1000)).await;
+            emit_event(&emitter, contract, &proof_fulfilled(1)).await;
+            emit_event(&emitter, contract, &proof_requested(2, 5)).await;
+            emitter.provider.anvil_mine(Some(2), None).await.unwrap();
+        });
+
+        let updates = tokio::time::timeout(Duration::from_secs(30), updates).await.unwrap();
+        assert!(matches!(&updates[0], QueueUpdate::NewRequest(r) if r.id == U256::from(1)));
+        assert!(matches!(&updates[1], QueueUpdate::Fulfilled(f) if f.id == U256::from(1)));
+        assert!(matches!(&updates[2], QueueUpdate::NewRequest(r) if r.id == U256::from(2)));
+        assert_eq!(updates[3], QueueUpdate::Expired { id: U256::from(2) });
+    }
+
+    #[tokio::test]
+    async fn subscribe_reorgs_detects_replaced_head() {
+        let (_anvil, provider, chain_monitor, _) = setup_monitor(TOPIC_LOG_INIT_CODE).await;
+        let old_head = chain_monitor.current_chain_head().await.unwrap();
+
+        let (reorgs_tx, mut reorgs) = tokio::sync::mpsc::unbounded_channel();
+        let mut stream = Box::pin(chain_monitor.subscribe_reorgs());
+        tokio::spawn(async move {
+            while let Some(reorg) = stream.next().await {
+                reorgs_tx.send(reorg).unwrap();
+            }
+        });
+        // Let the stream observe the current head before replacing it.
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        // Replace the deployment block with an empty block.
+        provider
+            .anvil_reorg(alloy::rpc::types::anvil::ReorgOptions {
+                depth: 1,
+                tx_block_pairs: vec![],
+            })
+            .await
+            .unwrap();
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let reorg =
+            tokio::time::timeout(Duration::from_secs(30), reorgs.recv()).await.unwrap().unwrap();
+        assert_eq!(reorg.old_head, old_head);
+        assert_eq!(reorg.new_head.block_number, old_head.block_number);
+        assert_ne!(reorg.new_head.block_hash, old_head.block_hash);
+    }
+
+    #[tokio::test]
+    async fn chain_reorganization_recovery_returns_dropped_fulfillments() {
+        let (_anvil, provider, chain_monitor, contract) = setup_monitor(DATA_LOG_INIT_CODE).await;
+        emit_event(&chain_monitor, contract, &proof_fulfilled(1)).await;
+        emit_event(&chain_monitor, contract, &proof_fulfilled(2)).await;
+        let old_head = chain_monitor.current_chain_head().await.unwrap();
+
+        // Replace the block fulfilling request 2 with an empty block.
+        provider
+            .anvil_reorg(alloy::rpc::types::anvil::ReorgOptions {
+                depth: 1,
+                tx_block_pairs: vec![],
+            })
+            .await
+            .unwrap();
+        *chain_monitor.next_update.write().await = Instant::now();
+        let new_head = chain_monitor.current_chain_head().await.unwrap();
+        assert_ne!(new_head.block_hash, old_head.block_hash);
+
+        let dropped = chain_monitor
+            .chain_reorganization_recovery(ReorgEvent { old_head, new_head })
+            .await
+            .unwrap();
+        assert_eq!(dropped, vec![U256::from(2)]);
+    }
+
+    #[tokio::test]
+    async fn watch_verifier_contract_paused_reads_pause_state() {
+        let (_anvil, provider, chain_monitor, paused) = setup_monitor(RETURN_TRUE_INIT_CODE).await;
+        let unpausable = deploy_contract(&provider, REVERT_INIT_CODE).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+
+        let mut states = Box::pin(chain_monitor.watch_verifier_contract_paused(paused));
+        assert!(states.next().await.unwrap());
+        let mut states = Box::pin(chain_monitor.watch_verifier_contract_paused(unpausable));
+        assert!(!states.next().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn subscribe_proof_price_threshold_waits_for_price() {
+        // The price is the block number, so it only rises.
+        let (_anvil, _provider, chain_monitor, pricing) =
+            setup_monitor(BLOCK_NUMBER_INIT_CODE).await;
+
+        chain_monitor
+            .subscribe_proof_price_threshold(pricing, U256::from(100), Duration::from_secs(10))
+            .await
+            .unwrap();
+
+        let timeout = Duration::from_millis(500);
+        let err = chain_monitor
+            .subscribe_proof_price_threshold(pricing, U256::ZERO, timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast::<ChainMonitorErr>().unwrap(),
+            ChainMonitorErr::Timeout(t) if t == timeout
+        ));
+    }
+
+    #[tokio::test]
+    async fn watch_proof_prices_yields_changes() {
+        // The price reported by the contract is the block number, so it changes with every block.
+        let (_anvil, provider, chain_monitor, pricing) =
+            setup_monitor(BLOCK_NUMBER_INIT_CODE).await;
+
+        let mut prices = Box::pin(chain_monitor.watch_proof_prices(pricing));
+        assert_eq!(prices.next().await.unwrap(), (1, U256::from(1)));
+
+        provider.anvil_mine(Some(1), None).await.unwrap();
+        *chain_monitor.next_update.write().await = Instant::now();
+        assert_eq!(prices.next().await.unwrap(), (2, U256::from(2)));
+    }
+
+    #[tokio::test]
+    async fn get_logs_since_last_run_resumes_from_cursor() {
+        let cursor_file = tempfile::NamedTempFile::new().unwrap();
+        let cursor_path = cursor_file.path().to_path_buf();
+        let (_anvil, provider, chain_monitor, emitter) =
+            setup_monitor_with(TOPIC_LOG_INIT_CODE, |chain_monitor| {
+                chain_monitor.with_config(ChainMonitorConfig {
+                    cursor_path: Some(cursor_path),
+                    ..Default::default()
+                })
+            })
+            .await;
+        let emit = |topic1: u8| {
+            let input = [B256::ZERO.as_slice(), B256::repeat_byte(topic1).as_slice()].concat();
+            let tx = TransactionRequest::default().with_to(emitter).with_input(input);
+            let provider = provider.clone();
+            async move { provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap() }
+        };
+        let filter = Filter::new().address(emitter);
+
+        emit(1).await;
+        let mut cursor = PersistentCursor::default();
+        let logs =
+            chain_monitor.get_logs_since_last_run(&mut cursor, filter.clone()).await.unwrap();
+        assert_eq!(logs.len(), 1);
+
+        emit(2).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+        let logs =
+            chain_monitor.get_logs_since_last_run(&mut cursor, filter.clone()).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics()[1], B256::repeat_byte(2));
+
+        // Ranges longer than a page are fetched page by page.
+        provider.anvil_mine(Some(2 * LOG_PAGE_SIZE), None).await.unwrap();
+        emit(3).await;
+        *chain_monitor.next_update.write().await = Instant::now();
+        let logs = chain_monitor.get_logs_since_last_run(&mut cursor, filter).await.unwrap();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].topics()[1], B256::repeat_byte(3));
+
+        let head = chain_monitor.current_block_number().await.unwrap();
+        assert_eq!(cursor.last_processed_block, head);
+        assert_eq!(PersistentCursor::load(cursor_file.path()).await.unwrap(), cursor);
+    }
+
+    #[tokio::test]
+    async fn subscribe_base_fee_changes_yields_pairs() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+        let initial_base_fee =
+            chain_monitor.current_chain_head().await.unwrap().base_fee_per_gas.unwrap();
+
+        let mut changes = Box::pin(chain_monitor.subscribe_base_fee_changes());
+        // Let the stream observe the initial head, which does not yield a change.
+        let pending = tokio::time::timeout(Duration::from_millis(500), changes.next()).await;
+        assert!(pending.is_err());
+
+        const FIRST: u128 = 2_000_000_000;
+        const SECOND: u128 = 3_000_000_000;
+        provider.anvil_set_next_block_base_fee_per_gas(FIRST).await.unwrap();
+        provider.anvil_mine(Some(1), None).await.unwrap();
+        assert_eq!(changes.next().await, Some((initial_base_fee, FIRST)));
+
+        provider.anvil_set_next_block_base_fee_per_gas(SECOND).await.unwrap();
+        provider.anvil_mine(Some(1), None).await.unwrap();
+        assert_eq!(changes.next().await, Some((FIRST, SECOND)));
+    }
+
+    #[tokio::test]
+    async fn decode_receipt_logs_decodes_all_events() {
+        let anvil = Anvil::new().spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let provider = ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect(&anvil.endpoint())
+            .await
+            .unwrap();
+
+        let emitter = deploy_contract(&provider, TRIPLE_LOG_INIT_CODE).await;
+        let tx = TransactionRequest::default()
+            .with_to(emitter)
+            .with_input(Bytes::copy_from_slice(Ping::SIGNATURE_HASH.as_slice()));
+        let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+
+        let decoded = ChainMonitorService::<()>::decode_receipt_logs::<Ping>(&receipt);
+        assert_eq!(decoded.len(), 3);
+        assert!(decoded.iter().all(|res| res.is_ok()));
+        assert!(ChainMonitorService::<()>::decode_first_log::<Ping>(&receipt).unwrap().is_some());
+    }
+
+    #[cfg(feature = "etherscan")]
+    #[tokio::test]
+    async fn get_contract_abi_from_etherscan_queries_explorer() {
+        use httpmock::prelude::*;
+
+        const ABI: &str = r#"[{"type":"function","name":"paused","inputs":[],"outputs":[]}]"#;
+        let address = Address::repeat_byte(0x22);
+
+        let server = MockServer::start();
+        let abi_mock = server.mock(|when, then| {
+            when.method(GET)
+                .path("/api")
+                .query_param("chainid", "31337")
+                .query_param("module", "contract")
+                .query_param("action", "getabi")
+                .query_param("address", address.to_string())
+                .query_param("apikey", "test-key");
+            then.status(200).json_body(serde_json::json!({
+                "status": "1",
+                "message": "OK",
+                "result": ABI,
+            }));
+        });
+
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor =
+            ChainMonitorService::new(provider).await.unwrap().with_config(ChainMonitorConfig {
+                etherscan_api_url: server.url("/api"),
+                etherscan_api_key: Some("test-key".to_string()),
+                ..Default::default()
+            });
+
+        assert_eq!(chain_monitor.get_contract_abi_from_etherscan(address).await.unwrap(), ABI);
+        // Served from the cache on the second call.
+        assert_eq!(chain_monitor.get_contract_abi_from_etherscan(address).await.unwrap(), ABI);
+        abi_mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn block_header_rlp_matches_block_hash() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider.clone()).await.unwrap();
+
+        let rlp = chain_monitor.block_header_rlp(0).await.unwrap();
+        let genesis = provider.get_block_by_number(BlockNumberOrTag::Number(0)).await.unwrap();
+        assert_eq!(keccak256(rlp), genesis.unwrap().header.hash);
+    }
+
+    #[tokio::test]
+    async fn account_state_root_is_non_zero() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let state_root = chain_monitor.account_state_root(0).await.unwrap();
+        assert!(!state_root.is_zero());
+        assert_eq!(chain_monitor.current_state_root().await.unwrap(), state_root);
+    }
+
+    #[tokio::test]
+    async fn is_canonical_chain_follows_parent_hashes() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        provider.anvil_mine(Some(5), None).await.unwrap();
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let mut blocks =
+            try_join_all((1..=5).map(|number| chain_monitor.block_head(number))).await.unwrap();
+        assert!(ChainMonitorService::<()>::is_canonical_chain(&blocks));
+        assert_eq!(chain_monitor.block_parent_hash(2).await.unwrap(), blocks[0].block_hash);
+
+        blocks.swap(1, 2);
+        assert!(!ChainMonitorService::<()>::is_canonical_chain(&blocks));
+    }
+
+    #[tokio::test]
+    async fn get_log_proof_verifies_against_receipts_root() {
+        let (_anvil, provider, chain_monitor, emitter) = setup_monitor(TRIPLE_LOG_INIT_CODE).await;
+        let tx = TransactionRequest::default()
+            .with_to(emitter)
+            .with_input(Bytes::copy_from_slice(Ping::SIGNATURE_HASH.as_slice()));
+        let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        let log_index = receipt.inner.logs()[1].log_index.unwrap();
+
+        let proof = chain_monitor.get_log_proof(receipt.transaction_hash, log_index).await.unwrap();
+        assert_eq!(proof.log_index, log_index);
+        assert_eq!(proof.receipt_index, receipt.transaction_index.unwrap());
+
+        let block = chain_monitor.fetch_block(receipt.block_number.unwrap()).await.unwrap();
+        alloy::trie::proof::verify_proof(
+            block.header.receipts_root,
+            Nibbles::unpack(alloy::rlp::encode_fixed_size(&proof.receipt_index)),
+            Some(encode_receipt(&receipt)),
+            &proof.receipt_proof,
+        )
+        .unwrap();
+
+        assert!(chain_monitor
+            .get_log_proof(receipt.transaction_hash, log_index + 10)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn etherscan_link_formats_explorer_urls() {
+        let anvil = Anvil::new().chain_id(1).spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+
+        let hash = B256::repeat_byte(0xab).to_string();
+        let address = Address::repeat_byte(0x11).to_string();
+        assert_eq!(chain_monitor.etherscan_link(&hash), format!("https://etherscan.io/tx/{hash}"));
+        assert_eq!(
+            chain_monitor.etherscan_link(&address),
+            format!("https://etherscan.io/address/{address}")
+        );
+
+        let chain_monitor = chain_monitor.with_config(ChainMonitorConfig {
+            chain_explorer_url: Some("https://explorer.example/".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(
+            chain_monitor.etherscan_link(&hash),
+            format!("https://explorer.example/tx/{hash}")
+        );
+
+        // Chains without a known explorer get relative links.
+        let anvil = Anvil::new().chain_id(888833888).spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+        assert_eq!(chain_monitor.etherscan_link(&hash), format!("/tx/{hash}"));
+    }
+
+    #[tokio::test]
+    async fn batch_nonces_preserves_order() {
+        let anvil = Anvil::new().spawn();
+        let signers: Vec<PrivateKeySigner> =
+            anvil.keys()[..5].iter().cloned().map(Into::into).collect();
+        let mut wallet = EthereumWallet::from(signers[0].clone());
+        for signer in &signers[1..] {
+            wallet.register_signer(signer.clone());
+        }
+        let provider = Arc::new(
+            ProviderBuilder::new().wallet(wallet).connect(&anvil.endpoint()).await.unwrap(),
+        );
+
+        // Send `i` transactions from the `i`-th address.
+        for (i, signer) in signers.iter().enumerate() {
+            for _ in 0..i {
+                let tx = TransactionRequest::default()
+                    .with_from(signer.address())
+                    .with_to(Address::repeat_byte(0x33));
+                provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+            }
+        }
+
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let addresses: Vec<Address> = signers.iter().rev().map(|signer| signer.address()).collect();
+        let nonces = chain_monitor.batch_nonces(addresses.clone()).await.unwrap();
+        let expected: Vec<(Address, u64)> = addresses.into_iter().zip([4, 3, 2, 1, 0]).collect();
+        assert_eq!(nonces, expected);
+    }
+
+    #[tokio::test]
+    async fn block_explorer_urls_use_chain_explorer() {
+        let tx_hash = B256::repeat_byte(0xab);
+        for (chain_id, base_url) in [(1, "https://etherscan.io"), (42161, "https://arbiscan.io")] {
+            let anvil = Anvil::new().chain_id(chain_id).spawn();
+            let provider =
+                Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+            let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+
+            assert_eq!(
+                chain_monitor.block_explorer_tx_url(tx_hash),
+                format!("{base_url}/tx/{tx_hash}")
+            );
+            assert_eq!(chain_monitor.block_explorer_block_url(42), format!("{base_url}/block/42"));
+            assert_eq!(
+                chain_monitor.block_explorer_address_url(Address::ZERO),
+                format!("{base_url}/address/{}", Address::ZERO)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn batch_storage_reads_preserves_order() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let address = Address::repeat_byte(0x44);
+        for i in 0..10u8 {
+            provider
+                .anvil_set_storage_at(address, U256::from(i), B256::repeat_byte(i + 1))
+                .await
+                .unwrap();
+        }
+        provider.anvil_mine(Some(1), None).await.unwrap();
+
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let slots: Vec<U256> = (0..10u8).rev().map(U256::from).collect();
+        let values = chain_monitor.batch_storage_reads(address, slots.clone()).await.unwrap();
+        let expected: Vec<(U256, U256)> = slots
+            .into_iter()
+            .map(|slot| (slot, U256::from_be_bytes([slot.to::<u8>() + 1; 32])))
+            .collect();
+        assert_eq!(values, expected);
+    }
+
+    #[cfg(feature = "flashbots")]
+    #[tokio::test]
+    async fn simulate_bundle_calls_eth_call_bundle() {
+        use httpmock::prelude::*;
+
+        let tx_hash = B256::repeat_byte(0x55);
+        let server = MockServer::start();
+        server.mock(|when, then| {
+            when.method(POST).body_contains("eth_chainId");
+            then.status(200).json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 0,
+                "result": "0x1",
+            }));
+        });
+        let bundle_mock = server.mock(|when, then| {
+            when.method(POST).body_contains("\"eth_callBundle\"").body_contains("\"0xb\"");
+            then.status(200).json_body(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "result": {
+                    "results": [{ "txHash": tx_hash, "gasUsed": 21000, "value": "0x" }],
+                },
+            }));
+        });
+
+        let provider = Arc::new(ProviderBuilder::new().connect(&server.url("/")).await.unwrap());
+        let chain_monitor = ChainMonitorService::new(provider).await.unwrap();
+        let tx = TransactionRequest::default().with_to(Address::repeat_byte(0x33));
+        let results = chain_monitor.simulate_bundle_at(vec![tx], 10).await.unwrap();
+
+        bundle_mock.assert();
+        assert_eq!(
+            results,
+            vec![SimulationResult {
+                tx_hash,
+                gas_used: 21000,
+                value: Some(Bytes::new()),
+                error: None,
+                revert: None,
+            }]
+        );
+    }
+
+    #[cfg(feature = "storage-layout")]
+    #[tokio::test]
+    async fn contract_storage_layout_matches_code_hash() {
+        let (_anvil, provider, chain_monitor, emitter) =
+            setup_monitor_with(TRIPLE_LOG_INIT_CODE, |chain_monitor| {
+                chain_monitor.with_config(ChainMonitorConfig {
+                    storage_layout_dir: Some(
+                        concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/storage_layout").into(),
+                    ),
+                    ..Default::default()
+                })
+            })
+            .await;
+
+        let layout = chain_monitor.contract_storage_layout(emitter).await.unwrap();
+        assert_eq!(layout["storage"][0]["label"], "count");
+
+        let other = deploy_contract(&provider, TOPIC_LOG_INIT_CODE).await;
+        assert!(chain_monitor.contract_storage_layout(other).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn nonce_safe_transfer_assigns_distinct_nonces() {
+        let anvil = Anvil::new().spawn();
+        let provider = Arc::new(ProviderBuilder::new().connect(&anvil.endpoint()).await.unwrap());
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider).await.unwrap());
+        let address = anvil.addresses()[0];
+
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let chain_monitor = chain_monitor.clone();
+                tokio::spawn(async move {
+                    let guard = chain_monitor.nonce_safe_transfer(address).await.unwrap();
+                    let nonce = guard.nonce();
+                    guard.commit();
+                    nonce
+                })
+            })
+            .collect();
+        let mut nonces = try_join_all(tasks).await.unwrap();
+        nonces.sort_unstable();
+        assert_eq!(nonces, vec![0, 1, 2, 3, 4]);
+
+        // An uncommitted nonce is released for the next reservation.
+        let guard = chain_monitor.nonce_safe_transfer(address).await.unwrap();
+        assert_eq!(guard.nonce(), 5);
+        drop(guard);
+        assert_eq!(chain_monitor.nonce_safe_transfer(address).await.unwrap().nonce(), 5);
+    }
+
+    #[tokio::test]
+    async fn watch_account_nonce_yields_increases() {
+        let anvil = Anvil::new().spawn();
+        let signer: PrivateKeySigner = anvil.keys()[0].clone().into();
+        let sender = signer.address();
+        let provider = Arc::new(
+            ProviderBuilder::new()
+                .wallet(EthereumWallet::from(signer))
+                .connect(&anvil.endpoint())
+                .await
+                .unwrap(),
+        );
+
+        let chain_monitor = Arc::new(ChainMonitorService::new(provider.clone()).await.unwrap());
+        tokio::spawn(chain_monitor.spawn(CancellationToken::new()));
+
+        let mut nonces = Box::pin(chain_monitor.watch_account_nonce(sender));
+        assert_eq!(nonces.next().await, Some(0));
+
+        let tx = TransactionRequest::default().with_to(Address::repeat_byte(0x33));
+        provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+        assert_eq!(nonces.next().await, Some(1));
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Pool of chain monitors over redundant RPC endpoints of the same chain, ranked by latency so
+//! that callers can prefer the fastest endpoint.
+
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use alloy::providers::Provider;
+use anyhow::{bail, Result};
+use futures::future::join_all;
+use tokio_util::sync::CancellationToken;
+
+use super::{ChainMonitorErr, ChainMonitorService};
+use crate::task::{RetryRes, RetryTask};
+
+/// Default interval between health checks of the pooled endpoints.
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Chain monitors of redundant RPC endpoints, ranked by the latency measured by a periodic
+/// health check.
+#[derive(Clone)]
+pub struct RpcPool<P> {
+    monitors: Arc<Vec<Arc<ChainMonitorService<P>>>>,
+    /// Indices of `monitors`, fastest first, with unreachable endpoints last.
+    ranking: Arc<RwLock<Vec<usize>>>,
+    health_check_interval: Duration,
+}
+
+impl<P: Provider> RpcPool<P> {
+    /// Creates a pool over `monitors`, ranked in the given order until the first health check.
+    pub fn new(monitors: Vec<Arc<ChainMonitorService<P>>>) -> Result<Self> {
+        if monitors.is_empty() {
+            bail!("RPC pool requires at least one endpoint");
+        }
+        Ok(Self {
+            ranking: Arc::new(RwLock::new((0..monitors.len()).collect())),
+            monitors: Arc::new(monitors),
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+        })
+    }
+
+    /// Replaces the default interval between health checks.
+    pub fn with_health_check_interval(self, health_check_interval: Duration) -> Self {
+        Self { health_check_interval, ..self }
+    }
+
+    /// Returns the monitor of the fastest endpoint at the last health check.
+    pub fn fastest(&self) -> Arc<ChainMonitorService<P>> {
+        self.monitors[self.ranking.read().unwrap()[0]].clone()
+    }
+
+    /// Measures the latency of each endpoint with [ChainMonitorService::measure_rpc_latency] and
+    /// ranks them by it, fastest first.
+    ///
+    /// Endpoints whose probe fails are ranked last, in their previous order.
+    pub async fn health_check(&self) {
+        let latencies =
+            join_all(self.monitors.iter().enumerate().map(|(index, monitor)| async move {
+                monitor
+                    .measure_rpc_latency()
+                    .await
+                    .inspect_err(|err| {
+                        tracing::warn!("Failed to measure latency of RPC endpoint {index}: {err:?}")
+                    })
+                    .ok()
+            }))
+            .await;
+
+        let mut ranking = self.ranking.write().unwrap();
+        // The sort is stable, so unreachable endpoints keep their previous relative order.
+        ranking.sort_by_key(|index| latencies[*index].unwrap_or(Duration::MAX));
+        tracing::debug!("Ranked RPC endpoints by latency: {ranking:?}");
+    }
+}
+
+impl<P> RetryTask for RpcPool<P>
+where
+    P: Provider + Clone + 'static,
+{
+    type Error = ChainMonitorErr;
+
+    fn spawn(&self, cancel_token: CancellationToken) -> RetryRes<Self::Error> {
+        let this = self.clone();
+        Box::pin(async move {
+            let mut interval = tokio::time::interval(this.health_check_interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => this.health_check().await,
+                    _ = cancel_token.cancelled() => break,
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{node_bindings::Anvil, providers::ProviderBuilder};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn health_check_ranks_unreachable_endpoints_last() {
+        let anvil = Anvil::new().spawn();
+        let unreachable = Anvil::new().spawn();
+        let mut monitors = Vec::new();
+        for endpoint in [unreachable.endpoint(), anvil.endpoint()] {
+            let provider = Arc::new(ProviderBuilder::new().connect(&endpoint).await.unwrap());
+            monitors.push(Arc::new(ChainMonitorService::new(provider).await.unwrap()));
+        }
+        drop(unreachable);
+
+        let pool = RpcPool::new(monitors).unwrap();
+        assert!(Arc::ptr_eq(&pool.fastest(), &pool.monitors[0]));
+
+        pool.health_check().await;
+        assert!(Arc::ptr_eq(&pool.fastest(), &pool.monitors[1]));
+        assert!(pool.fastest().current_rpc_latency_ms().is_some());
+        assert!(pool.monitors[0].current_rpc_latency_ms().is_none());
+    }
+}
@@ -0,0 +1,206 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compact JSON encoding for the chain monitor types.
+//!
+//! Block numbers and timestamps are encoded as JSON integers, rather than the hex quantities of
+//! the Ethereum JSON-RPC API, while hashes keep their `0x`-prefixed hex encoding. Optional header
+//! fields are omitted when absent.
+
+use alloy::primitives::{B256, U256};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::ChainHead;
+
+/// Wire format of a [ChainHead], using the JSON-RPC block header field names.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ChainHeadRepr {
+    number: u64,
+    timestamp: u64,
+    hash: B256,
+    #[serde(default, skip_serializing_if = "B256::is_zero")]
+    parent_hash: B256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    l1_block_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    withdrawals_root: Option<B256>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    has_withdrawals: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base_fee_per_gas: Option<u128>,
+    #[serde(default, skip_serializing_if = "B256::is_zero")]
+    state_root: B256,
+    #[serde(default, skip_serializing_if = "B256::is_zero")]
+    transactions_root: B256,
+    #[serde(default, skip_serializing_if = "B256::is_zero")]
+    receipts_root: B256,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    difficulty: Option<U256>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mix_hash: Option<B256>,
+}
+
+impl From<ChainHead> for ChainHeadRepr {
+    fn from(head: ChainHead) -> Self {
+        Self {
+            number: head.block_number,
+            timestamp: head.block_timestamp,
+            hash: head.block_hash,
+            parent_hash: head.parent_hash,
+            l1_block_number: head.l1_block_number,
+            withdrawals_root: head.withdrawals_root,
+            has_withdrawals: head.has_withdrawals,
+            base_fee_per_gas: head.base_fee_per_gas,
+            state_root: head.state_root,
+            transactions_root: head.transactions_root,
+            receipts_root: head.receipts_root,
+            difficulty: head.difficulty,
+            mix_hash: head.mix_hash,
+        }
+    }
+}
+
+impl From<ChainHeadRepr> for ChainHead {
+    fn from(repr: ChainHeadRepr) -> Self {
+        Self {
+            block_number: repr.number,
+            block_timestamp: repr.timestamp,
+            block_hash: repr.hash,
+            parent_hash: repr.parent_hash,
+            l1_block_number: repr.l1_block_number,
+            withdrawals_root: repr.withdrawals_root,
+            has_withdrawals: repr.has_withdrawals,
+            base_fee_per_gas: repr.base_fee_per_gas,
+            state_root: repr.state_root,
+            transactions_root: repr.transactions_root,
+            receipts_root: repr.receipts_root,
+            difficulty: repr.difficulty,
+            mix_hash: repr.mix_hash,
+        }
+    }
+}
+
+impl Serialize for ChainHead {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ChainHeadRepr::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChainHead {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        ChainHeadRepr::deserialize(deserializer).map(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::chain_monitor::ChainMonitorStatus;
+
+    const HASH: B256 = B256::repeat_byte(0xab);
+
+    #[test]
+    fn chain_head_roundtrip() {
+        let head = ChainHead {
+            block_number: 12345678,
+            block_timestamp: 1714000000,
+            block_hash: HASH,
+            ..Default::default()
+        };
+
+        let value = serde_json::to_value(head).unwrap();
+        assert_eq!(
+            value,
+            json!({ "number": 12345678, "timestamp": 1714000000, "hash": HASH.to_string() })
+        );
+        assert_eq!(serde_json::from_value::<ChainHead>(value).unwrap(), head);
+    }
+
+    #[test]
+    fn chain_head_optional_fields_roundtrip() {
+        let head = ChainHead {
+            block_number: 1,
+            block_timestamp: 2,
+            block_hash: HASH,
+            parent_hash: HASH,
+            l1_block_number: Some(3),
+            withdrawals_root: Some(HASH),
+            has_withdrawals: true,
+            base_fee_per_gas: Some(7),
+            state_root: HASH,
+            transactions_root: HASH,
+            receipts_root: HASH,
+            difficulty: Some(U256::from(9)),
+            mix_hash: Some(HASH),
+        };
+
+        let value = serde_json::to_value(head).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "number": 1,
+                "timestamp": 2,
+                "hash": HASH.to_string(),
+                "parentHash": HASH.to_string(),
+                "l1BlockNumber": 3,
+                "withdrawalsRoot": HASH.to_string(),
+                "hasWithdrawals": true,
+                "baseFeePerGas": 7,
+                "stateRoot": HASH.to_string(),
+                "transactionsRoot": HASH.to_string(),
+                "receiptsRoot": HASH.to_string(),
+                "difficulty": "0x9",
+                "mixHash": HASH.to_string(),
+            })
+        );
+        assert_eq!(serde_json::from_value::<ChainHead>(value).unwrap(), head);
+    }
+
+    #[test]
+    fn chain_head_rejects_hex_quantities() {
+        let value = json!({ "number": "0x1", "timestamp": 2, "hash": HASH.to_string() });
+        assert!(serde_json::from_value::<ChainHead>(value).is_err());
+    }
+
+    #[test]
+    fn chain_monitor_status_roundtrip() {
+        let status = ChainMonitorStatus {
+            chain_id: 1,
+            head: ChainHead {
+                block_number: 10,
+                block_timestamp: 20,
+                block_hash: HASH,
+                ..Default::default()
+            },
+            gas_price: 1_000_000_000,
+            queue_depth: None,
+            net_listening: None,
+            fulfillment_rate: None,
+        };
+
+        let value = serde_json::to_value(&status).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "chainId": 1,
+                "head": { "number": 10, "timestamp": 20, "hash": HASH.to_string() },
+                "gasPrice": 1_000_000_000u64,
+            })
+        );
+        assert_eq!(serde_json::from_value::<ChainMonitorStatus>(value).unwrap(), status);
+    }
+}
@@ -866,7 +866,7 @@ where
 
                 // On each interval, process all pending orders and do the block-based logic
                 _ = interval.tick() => {
-                    let ChainHead { block_number, block_timestamp } =
+                    let ChainHead { block_number, block_timestamp, .. } =
                         self.chain_monitor.current_chain_head().await?;
                     if block_number != last_block {
                         last_block = block_number;
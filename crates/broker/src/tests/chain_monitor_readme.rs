@@ -0,0 +1,48 @@
+// Copyright 2025 RISC Zero, Inc.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Checks that the chain monitor README matches the doc-comments it is generated from.
+
+use std::{env, fs, path::Path};
+
+// The build script only reads part of the shared module.
+#[allow(dead_code)]
+#[path = "../../build/chain_monitor_api.rs"]
+mod chain_monitor_api;
+
+use chain_monitor_api::{ChainMonitorApi, CHAIN_MONITOR_DIR, README};
+
+#[test]
+fn readme_is_up_to_date() {
+    let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let readme_path = manifest_dir.join(CHAIN_MONITOR_DIR).join(README);
+    let readme = ChainMonitorApi::parse(manifest_dir).render_readme();
+
+    if env::var_os("UPDATE_README").is_some() {
+        fs::write(&readme_path, readme).unwrap();
+        return;
+    }
+    assert_eq!(
+        fs::read_to_string(&readme_path).unwrap(),
+        readme,
+        "{} is out of date, regenerate it with `UPDATE_README=1 cargo test -p broker chain_monitor_readme`",
+        readme_path.display()
+    );
+}
+
+#[test]
+fn public_functions_are_documented() {
+    let api = ChainMonitorApi::parse(Path::new(env!("CARGO_MANIFEST_DIR")));
+    assert!(api.undocumented.is_empty(), "undocumented public functions: {:?}", api.undocumented);
+}